@@ -1,5 +1,5 @@
 cd tests
-DEV_MAPPINGS=1 cargo build
+cargo build --features dev
 # cargo build
 cd ..
 cp ./tests/target/debug/librustcraft_test.so ./build/out/
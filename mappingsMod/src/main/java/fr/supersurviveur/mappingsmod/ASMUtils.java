@@ -1,6 +1,7 @@
 package fr.supersurviveur.mappingsmod;
 
 import org.objectweb.asm.ClassReader;
+import org.objectweb.asm.Type;
 import org.objectweb.asm.tree.AnnotationNode;
 import org.objectweb.asm.tree.ClassNode;
 import org.objectweb.asm.tree.FieldNode;
@@ -44,8 +45,14 @@ public class ASMUtils {
     }
 
     public static boolean isNullable(MethodNode methodNode, int argPos) {
-        if (methodNode.visibleParameterAnnotations != null && methodNode.visibleParameterAnnotations[argPos] != null) {
-            for (AnnotationNode annotation : methodNode.visibleParameterAnnotations[argPos]) {
+        if (methodNode.visibleParameterAnnotations == null) {
+            return false;
+        }
+        // javac leaves out the first synthetic parameters, like the outer instance taken by
+        // constructors of inner classes, so annotations are those of the last parameters
+        int index = argPos - (Type.getArgumentTypes(methodNode.desc).length - methodNode.visibleParameterAnnotations.length);
+        if (index >= 0 && index < methodNode.visibleParameterAnnotations.length && methodNode.visibleParameterAnnotations[index] != null) {
+            for (AnnotationNode annotation : methodNode.visibleParameterAnnotations[index]) {
                 if (annotation.desc.equals("Lorg/jetbrains/annotations/Nullable;")) {
                     return true;
                 }
@@ -127,6 +134,14 @@ public class ASMUtils {
         return java.lang.reflect.Modifier.isStatic(field.getModifiers());
     }
 
+    public static boolean isFinal(Executable method) {
+        return java.lang.reflect.Modifier.isFinal(method.getModifiers());
+    }
+
+    public static boolean isFinal(Field field) {
+        return java.lang.reflect.Modifier.isFinal(field.getModifiers());
+    }
+
     public static char getModifiers(ModifiersLambda f, String className, String method, String methodSig) throws ReflectiveOperationException {
         // Parse signature to get the method signature
         MethodType sig;
@@ -182,6 +197,9 @@ public class ASMUtils {
             if (isStatic(m)) {
                 modifiers |= Modifiers.Static;
             }
+            if (isFinal(m)) {
+                modifiers |= Modifiers.Final;
+            }
 
             return modifiers;
         }, className, method, methodSig);
@@ -244,6 +262,12 @@ public class ASMUtils {
         if (isStatic(f)) {
             modifiers |= Modifiers.Static;
         }
+        if (isFinal(f)) {
+            modifiers |= Modifiers.Final;
+        }
+        if (f.isEnumConstant()) {
+            modifiers |= Modifiers.Enum;
+        }
 
         return modifiers;
     }
@@ -5,4 +5,6 @@ public class Modifiers {
     public static char None = 0;
     public static char Static = 1;
     public static char Nullable = 2;
+    public static char Final = 4;
+    public static char Enum = 8;
 }
@@ -0,0 +1,18 @@
+use rustcraft_mappings::{jar, MappingsSource};
+
+/// Cache the mappings and record their key before the proc macros of this crate look them up
+pub fn main() {
+    println!("cargo::rerun-if-env-changed=RUSTCRAFT_MAPPINGS");
+    println!("cargo::rerun-if-env-changed=RUSTCRAFT_MOJANG_MAPPINGS");
+    println!("cargo::rerun-if-env-changed=RUSTCRAFT_MINECRAFT_JAR");
+    println!("cargo::rerun-if-env-changed=RUSTCRAFT_GRADLE_PROPERTIES");
+    println!("cargo::rerun-if-env-changed=RUSTCRAFT_CACHE_DIR");
+    println!("cargo::rerun-if-env-changed=GRADLE_USER_HOME");
+    // Missing paths would rerun this script on every build
+    let source = MappingsSource::detect();
+    let inputs = [Some(source.path().to_owned()), jar::find_minecraft_jar()];
+    for path in inputs.into_iter().flatten().filter(|path| path.exists()) {
+        println!("cargo::rerun-if-changed={}", path.display());
+    }
+    rustcraft_mappings::prepare_mappings();
+}
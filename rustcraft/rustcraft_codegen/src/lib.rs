@@ -5,7 +5,7 @@ use proc_macro::TokenStream;
 use proc_macro_error::{abort, proc_macro_error};
 use quote::{format_ident, quote};
 use rustcraft_mappings::{
-    codegen::auto_gen_impl, convert_sig, get_class, get_multiple_class, rust_to_java_method,
+    classes, codegen::auto_gen_impl, convert_sig, get_class, rust_to_java_method, MappingsError,
 };
 use syn::{
     parse::Parse, parse_macro_input, spanned::Spanned, ImplItem, ImplItemMethod, ItemImpl, LitStr,
@@ -14,6 +14,11 @@ use syn::{
 
 mod to_java;
 
+/// Abort with the error of a mappings lookup, pointing at the literal which caused it
+fn or_abort<T>(result: Result<T, MappingsError>, span: impl Spanned) -> T {
+    result.unwrap_or_else(|error| abort!(span.span(), "{}", error))
+}
+
 #[proc_macro_attribute]
 pub fn rust_mod(_attr: TokenStream, item: TokenStream) -> TokenStream {
     let user_mod = parse_macro_input!(item as syn::ItemStruct);
@@ -33,6 +38,7 @@ pub fn rust_mod(_attr: TokenStream, item: TokenStream) -> TokenStream {
     .into()
 }
 
+#[proc_macro_error]
 #[proc_macro_attribute]
 pub fn to_java(attr: TokenStream, input: TokenStream) -> TokenStream {
     // Match input type to know which macro to call
@@ -43,33 +49,19 @@ pub fn to_java(attr: TokenStream, input: TokenStream) -> TokenStream {
     }
 }
 
+#[proc_macro_error]
 #[proc_macro_attribute]
 pub fn to_java_method(attr: TokenStream, input: TokenStream) -> TokenStream {
     let mut f = parse_macro_input!(input as syn::ItemFn);
     let class = parse_macro_input!(attr as LitStr);
 
-    let name = f.sig.ident;
-    let name = name.to_string();
+    let name = f.sig.ident.to_string();
     let (name, method_name) = name.rsplit_once("_").unwrap();
 
-    let binding = class.value();
-    let mappings = get_class(&binding);
-
-    f.sig.ident = format_ident!(
-        "{}_{}",
-        name,
-        mappings
-            .get_method(method_name)
-            .expect(
-                format!(
-                    "Can't find method {} with and without signature",
-                    method_name
-                )
-                .as_str()
-            )
-            .get_java_name()
-            .replace("_", "_1")
-    );
+    let mappings = or_abort(get_class(&class.value()), &class);
+    let method = or_abort(classes().method(mappings, method_name), &f.sig.ident);
+
+    f.sig.ident = format_ident!("{}_{}", name, method.get_java_name().replace("_", "_1"));
     quote::quote! {
         #f
     }
@@ -91,43 +83,26 @@ impl Parse for MappingsParam {
     }
 }
 
+#[proc_macro_error]
 #[proc_macro]
 pub fn mappings(input: TokenStream) -> TokenStream {
     let f = parse_macro_input!(input as MappingsParam);
 
-    let binding = f.class.value();
-    let mappings = get_class(&binding);
-
-    let result = mappings
-        .get_method(&f.name.value())
-        .expect(
-            format!(
-                "Can't find method {} with and without signature",
-                f.name.value()
-            )
-            .as_str(),
-        )
-        .get_java_name();
+    let mappings = or_abort(get_class(&f.class.value()), &f.class);
+    let result = or_abort(classes().method(mappings, &f.name.value()), &f.name).get_java_name();
     quote::quote! {
         #result
     }
     .into()
 }
 
+#[proc_macro_error]
 #[proc_macro]
 pub fn mappings_with_sig(input: TokenStream) -> TokenStream {
     let f = parse_macro_input!(input as MappingsParam);
 
-    let binding = f.class.value();
-    let mappings = get_class(&binding);
-
-    let method = mappings.get_method(&f.name.value()).expect(
-        format!(
-            "Can't find method {} with and without signature",
-            f.name.value()
-        )
-        .as_str(),
-    );
+    let mappings = or_abort(get_class(&f.class.value()), &f.class);
+    let method = or_abort(classes().method(mappings, &f.name.value()), &f.name);
     let method_name = method.get_java_name();
     let sig = method.get_java_sig();
     quote::quote! {
@@ -136,10 +111,24 @@ pub fn mappings_with_sig(input: TokenStream) -> TokenStream {
     .into()
 }
 
+/// Name of a class in the `TARGET` namespace
+#[proc_macro_error]
+#[proc_macro]
+pub fn mappings_class(input: TokenStream) -> TokenStream {
+    let class = parse_macro_input!(input as LitStr);
+    let result = or_abort(get_class(&class.value()), &class).get_java_name();
+
+    quote::quote! {
+        #result
+    }
+    .into()
+}
+
+#[proc_macro_error]
 #[proc_macro]
 pub fn mappings_sig(input: TokenStream) -> TokenStream {
     let sig = parse_macro_input!(input as LitStr);
-    let result = convert_sig(&sig.value());
+    let result = or_abort(convert_sig(&sig.value()), &sig);
 
     quote::quote! {
         #result
@@ -213,10 +202,16 @@ pub fn block(_attr: TokenStream, item: TokenStream) -> TokenStream {
         );
     }
 
-    let mappings = get_multiple_class(&[
-        "net/minecraft/block/Block",
-        "net/minecraft/block/AbstractBlock",
-    ]);
+    let block = or_abort(get_class("net/minecraft/block/Block"), &impl_item.self_ty);
+    // Inherited methods are found through the ancestors of `Block`, which are only unknown
+    // without the game jar. `AbstractBlock` declares most of them then
+    let abstract_block = match block.super_class {
+        Some(_) => None,
+        None => Some(or_abort(
+            get_class("net/minecraft/block/AbstractBlock"),
+            &impl_item.self_ty,
+        )),
+    };
     let mut overrided = vec![];
     let mut custom_funcs = vec![];
     for item in &impl_item.items {
@@ -230,76 +225,83 @@ pub fn block(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 }
                 let rust_method_name = method.sig.ident.to_string();
                 let rust_method_name_ident = format_ident!("{}", rust_method_name);
-                if let Some(mc_method) =
-                    mappings.get_method(&rust_to_java_method(&rust_method_name))
+                let java_method_name = rust_to_java_method(&rust_method_name);
+                let mut mc_method = classes().method(block, &java_method_name);
+                if let (Err(MappingsError::UnknownMember { .. }), Some(abstract_block)) =
+                    (&mc_method, abstract_block)
                 {
-                    let method_name = mc_method.get_java_name();
-                    let sig = mc_method.get_java_sig();
-                    let mut custom_func_args = vec![];
-                    let mut custom_func_args_names = vec![];
-                    let mut custom_func_body = quote! {};
-                    method
-                        .sig
-                        .inputs
-                        .iter()
-                        .skip(1) // Skip self and api
-                        .enumerate()
-                        .for_each(|(i, arg)| match arg {
-                            syn::FnArg::Typed(arg) => {
-                                let type_name = &arg.ty;
-                                let arg_name = format_ident!("var{}", i);
-                                custom_func_args.push(quote! {
-                                    #arg_name: rustcraft::prelude::JObject<'local>
-                                });
-                                custom_func_args_names.push(quote! {
-                                    &mut #arg_name
-                                });
-                                custom_func_body = quote! {
-                                    #custom_func_body
-                                    let mut #arg_name = rustcraft::net::minecraft::world::World::new(api.clone(), #arg_name);
-                                    // let #arg_name = #type_name::new(api.clone(), #arg_name);
-                                }
+                    // Unknown methods are reported on `Block`, where they are looked up
+                    match classes().method(abstract_block, &java_method_name) {
+                        Err(MappingsError::UnknownMember { .. }) => {}
+                        found => mc_method = found,
+                    }
+                }
+                let mc_method = or_abort(mc_method, &method.sig.ident);
+                let method_name = mc_method.get_java_name();
+                let sig = mc_method.get_java_sig();
+                let mut custom_func_args = vec![];
+                let mut custom_func_args_names = vec![];
+                let mut custom_func_body = quote! {};
+                method
+                    .sig
+                    .inputs
+                    .iter()
+                    .skip(1) // Skip self and api
+                    .enumerate()
+                    .for_each(|(i, arg)| match arg {
+                        syn::FnArg::Typed(arg) => {
+                            let type_name = &arg.ty;
+                            let arg_name = format_ident!("var{}", i);
+                            custom_func_args.push(quote! {
+                                #arg_name: rustcraft::prelude::JObject<'local>
+                            });
+                            custom_func_args_names.push(quote! {
+                                &mut #arg_name
+                            });
+                            custom_func_body = quote! {
+                                #custom_func_body
+                                let mut #arg_name = rustcraft::net::minecraft::world::World::new(api.clone(), #arg_name);
+                                // let #arg_name = #type_name::new(api.clone(), #arg_name);
                             }
-                            _ => {}
-                        });
-                    let has_return = match &method.sig.output {
-                        syn::ReturnType::Default => false,
-                        syn::ReturnType::Type(_, _) => true,
-                    };
-                    let (ret_t, ret) = if has_return {
-                        (
-                            quote! {
-                                -> rustcraft::prelude::JObject<'local>
-                            },
-                            quote! {
-                                unsafe { JObject::from_raw(rustcraft::object::ToJava::to_java(result, api.clone()).clone()) }
-                            },
-                        )
-                    } else {
-                        (quote! {}, quote! {})
-                    };
-                    let custom_ident = format_ident!("__rustcraft_{}", rust_method_name_ident);
-                    custom_funcs.push(quote! {
-                        pub extern "system" fn #custom_ident<
-                            'local,
-                        >(
-                            env: rustcraft::prelude::JNIEnv<'local>,
-                            obj: rustcraft::prelude::JObject<'local>,
-                            #(#custom_func_args),*
-                        ) #ret_t {
-                            let mut api = ModApi::new(env, obj);
-                            #custom_func_body
-
-                            let block: &mut Box<dyn rustcraft::block::Block> = rustcraft::prelude::load_object(api.clone(), None);
-
-                            let result = block.#rust_method_name_ident(#(#custom_func_args_names),*);
-                            #ret
                         }
+                        _ => {}
                     });
-                    overrided.push(
-                        quote! {(#method_name, #sig, #custom_ident as *mut std::ffi::c_void)},
-                    );
-                }
+                let has_return = match &method.sig.output {
+                    syn::ReturnType::Default => false,
+                    syn::ReturnType::Type(_, _) => true,
+                };
+                let (ret_t, ret) = if has_return {
+                    (
+                        quote! {
+                            -> rustcraft::prelude::JObject<'local>
+                        },
+                        quote! {
+                            unsafe { JObject::from_raw(rustcraft::object::ToJava::to_java(result, api.clone()).clone()) }
+                        },
+                    )
+                } else {
+                    (quote! {}, quote! {})
+                };
+                let custom_ident = format_ident!("__rustcraft_{}", rust_method_name_ident);
+                custom_funcs.push(quote! {
+                    pub extern "system" fn #custom_ident<
+                        'local,
+                    >(
+                        env: rustcraft::prelude::JNIEnv<'local>,
+                        obj: rustcraft::prelude::JObject<'local>,
+                        #(#custom_func_args),*
+                    ) #ret_t {
+                        let mut api = ModApi::new(env, obj);
+                        #custom_func_body
+
+                        let block: &mut Box<dyn rustcraft::block::Block> = rustcraft::prelude::load_object(api.clone(), None);
+
+                        let result = block.#rust_method_name_ident(#(#custom_func_args_names),*);
+                        #ret
+                    }
+                });
+                overrided
+                    .push(quote! {(#method_name, #sig, #custom_ident as *mut std::ffi::c_void)});
             }
             _ => {}
         }
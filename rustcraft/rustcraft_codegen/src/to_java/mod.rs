@@ -1,12 +1,16 @@
 use proc_macro::TokenStream;
+use rustcraft_mappings::classes;
 use syn::{parse_macro_input, ItemEnum};
 
+use crate::or_abort;
+
 pub fn enum_to_java_impl(attr: TokenStream, input: ItemEnum) -> TokenStream {
     // Parse attr to get name of the java class
     let class_name = parse_macro_input!(attr as syn::LitStr);
-    let binding = class_name.value();
-
-    let mappings = rustcraft_mappings::get_class(&binding);
+    let mappings = or_abort(
+        rustcraft_mappings::get_class(&class_name.value()),
+        &class_name,
+    );
     let class_name = mappings.get_java_name();
 
     let name = &input.ident;
@@ -16,16 +20,15 @@ pub fn enum_to_java_impl(attr: TokenStream, input: ItemEnum) -> TokenStream {
     for variant in &input.variants {
         let variant = &variant.ident;
         let variant_name = variant.to_string();
-        let field = mappings
-            .fields
-            .get(&variant_name)
-            .expect(format!("Variant {} doesn't exists", variant_name).as_str());
+        let field = or_abort(classes().field(mappings, &variant_name), variant);
 
         let variant_name = field.get_java_name();
         let type_name = field.get_java_type();
         match_cases.extend(quote::quote! {
             #name::#variant => {
-                api.get_static_field(&enum_class, (#variant_name, #type_name))
+                static FIELD: crate::cache::CachedStaticField =
+                    crate::cache::CachedStaticField::new(#class_name, #variant_name, #type_name);
+                &FIELD
             }
         });
     }
@@ -34,11 +37,10 @@ pub fn enum_to_java_impl(attr: TokenStream, input: ItemEnum) -> TokenStream {
         #input
         impl crate::object::ToJava for #name {
             fn to_java<'local>(self, api: ModApi<'local>) -> JObject<'local> {
-                let enum_class = api.get_class(#class_name);
-                let enum_field = match self {
+                let enum_field: &crate::cache::CachedStaticField = match self {
                     #match_cases
                 };
-                enum_field.l().unwrap()
+                api.get_cached_static_field(enum_field).l().unwrap()
             }
         }
     };
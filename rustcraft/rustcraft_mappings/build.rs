@@ -1,3 +1,8 @@
 pub fn main() {
-    println!("cargo::rerun-if-env-changed=DEV_MAPPINGS");
+    println!("cargo::rerun-if-env-changed=RUSTCRAFT_MAPPINGS");
+    println!("cargo::rerun-if-env-changed=RUSTCRAFT_MOJANG_MAPPINGS");
+    println!("cargo::rerun-if-env-changed=RUSTCRAFT_MINECRAFT_JAR");
+    println!("cargo::rerun-if-env-changed=RUSTCRAFT_GRADLE_PROPERTIES");
+    println!("cargo::rerun-if-env-changed=GRADLE_USER_HOME");
+    println!("cargo::rerun-if-env-changed=RUSTCRAFT_TYPE_MAPPINGS");
 }
@@ -0,0 +1,183 @@
+//! Parsed mappings cached on disk. Files are named after a hash of the content of everything the
+//! mappings are built from, so changing the mappings, the Minecraft version or the schema rebuilds
+//! them instead of reusing stale ones, while moving or touching the inputs doesn't
+use std::{
+    env,
+    fs::{self, File},
+    io::Read,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use crate::{
+    jar::{self, GradleVersions},
+    visit_dirs, Mappings, MappingsSource,
+};
+
+/// Bumped when the fields of `Mappings` or of the types it contains change
+pub const SCHEMA_VERSION: u32 = 2;
+const MAGIC: &[u8; 4] = b"RCMC";
+const HEADER_LEN: usize = 16;
+/// File of the cache directory holding the key of the last build, see `CacheKey::recorded`
+const RECORDED_KEY: &str = "mappings-key";
+
+static CURRENT_KEY: LazyLock<CacheKey> = LazyLock::new(|| CacheKey::new(&MappingsSource::detect()));
+
+/// FNV-1a, stable between runs and toolchains unlike `DefaultHasher`
+struct Fnv(u64);
+
+impl Fnv {
+    fn new() -> Self {
+        Fnv(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100_0000_01b3);
+        }
+    }
+
+    /// Hash `bytes` after their length, so consecutive values can't be confused
+    fn write(&mut self, bytes: &[u8]) {
+        self.write_bytes(&(bytes.len() as u64).to_le_bytes());
+        self.write_bytes(bytes);
+    }
+
+    /// Hash the content of a file followed by its length, streamed so large jars aren't read in
+    /// memory. Missing files hash like empty ones
+    fn write_file(&mut self, path: &Path) {
+        let mut len = 0u64;
+        if let Ok(mut file) = File::open(path) {
+            let mut buffer = vec![0; 1 << 16];
+            while let Ok(read @ 1..) = file.read(&mut buffer) {
+                self.write_bytes(&buffer[..read]);
+                len += read as u64;
+            }
+        }
+        self.write_bytes(&len.to_le_bytes());
+    }
+}
+
+/// Identity of the inputs of a cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheKey(pub u64);
+
+impl CacheKey {
+    /// Hash the content of the files of `source`, of the mojang mappings and of the Minecraft jar,
+    /// the Minecraft version and `SCHEMA_VERSION`. Enigma files are hashed with their path in the
+    /// checkout, so moving it keeps the key. The target namespace isn't part of it, as the cache
+    /// holds every namespace
+    pub fn new(source: &MappingsSource) -> Self {
+        let mut hasher = Fnv::new();
+        hasher.write(&SCHEMA_VERSION.to_le_bytes());
+        match source {
+            MappingsSource::Enigma(dir) => {
+                let mut files = vec![];
+                visit_dirs(dir, &mut |entry| files.push(entry.path())).ok();
+                files.sort();
+                for file in files {
+                    let relative = file.strip_prefix(dir).unwrap_or(&file);
+                    hasher.write(relative.to_string_lossy().as_bytes());
+                    hasher.write_file(&file);
+                }
+            }
+            MappingsSource::Tiny(file) | MappingsSource::Jar(file) => hasher.write_file(file),
+        }
+        if let Ok(path) = env::var("RUSTCRAFT_MOJANG_MAPPINGS") {
+            hasher.write_file(Path::new(&path));
+        }
+        let minecraft_version = GradleVersions::load().minecraft_version;
+        hasher.write(minecraft_version.unwrap_or_default().as_bytes());
+        if let Some(jar) = jar::find_minecraft_jar() {
+            hasher.write_file(&jar);
+        }
+        CacheKey(hasher.0)
+    }
+
+    /// Key of the mappings found by `MappingsSource::detect`, computed once as it reads them
+    pub fn current() -> Self {
+        *CURRENT_KEY
+    }
+
+    /// Key recorded by the last build step, see `prepare_mappings`. Proc macros read it instead of
+    /// hashing the mappings in every rustc process, and programs run outside of cargo, like
+    /// `rustcraft_mappings_gen` in the game, don't see the environment of the build
+    pub fn recorded() -> Option<Self> {
+        let key = fs::read_to_string(cache_dir().join(RECORDED_KEY)).ok()?;
+        u64::from_str_radix(key.trim(), 16).ok().map(CacheKey)
+    }
+
+    /// Make this key the one returned by `recorded`
+    pub fn record(&self) {
+        let key = format!("{:016x}", self.0);
+        let path = cache_dir().join(RECORDED_KEY);
+        if fs::read_to_string(&path).is_ok_and(|recorded| recorded == key) {
+            return;
+        }
+        let temporary = path.with_extension(format!("tmp{}", std::process::id()));
+        let written = fs::create_dir_all(cache_dir()).and_then(|_| fs::write(&temporary, key));
+        if written.and_then(|_| fs::rename(&temporary, &path)).is_err() {
+            fs::remove_file(&temporary).ok();
+        }
+    }
+
+    pub fn mappings_path(&self) -> PathBuf {
+        cache_dir().join(format!("mappings-{:016x}.bin", self.0))
+    }
+
+    pub fn index_path(&self) -> PathBuf {
+        cache_dir().join(format!("mappings-{:016x}.index", self.0))
+    }
+
+    fn header(&self) -> [u8; HEADER_LEN] {
+        let mut header = [0; HEADER_LEN];
+        header[..4].copy_from_slice(MAGIC);
+        header[4..8].copy_from_slice(&SCHEMA_VERSION.to_le_bytes());
+        header[8..].copy_from_slice(&self.0.to_le_bytes());
+        header
+    }
+}
+
+/// `RUSTCRAFT_CACHE_DIR` if set, otherwise `rustcraft` in the cache directory of the user
+pub fn cache_dir() -> PathBuf {
+    env::var_os("RUSTCRAFT_CACHE_DIR")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("XDG_CACHE_HOME").map(|dir| Path::new(&dir).join("rustcraft")))
+        .or_else(|| env::var_os("LOCALAPPDATA").map(|dir| Path::new(&dir).join("rustcraft")))
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache/rustcraft")))
+        .unwrap_or_else(|| env::temp_dir().join("rustcraft"))
+}
+
+/// Whether the cache of `key` and its index were written
+pub fn exists(key: CacheKey) -> bool {
+    let mut header = [0; HEADER_LEN];
+    let written = File::open(key.mappings_path()).and_then(|mut file| file.read_exact(&mut header));
+    written.is_ok() && header == key.header() && key.index_path().is_file()
+}
+
+/// `None` if the cache is missing, or was written for other inputs or by another schema
+pub fn read(key: CacheKey) -> Option<Mappings> {
+    let bytes = fs::read(key.mappings_path()).ok()?;
+    let (header, content) = bytes.split_at_checked(HEADER_LEN)?;
+    if header != key.header() {
+        return None;
+    }
+    bitcode::decode(content).ok()
+}
+
+pub fn write(key: CacheKey, mappings: &Mappings) {
+    let mut bytes = key.header().to_vec();
+    bytes.extend(bitcode::encode(mappings));
+    write_atomic(&key.mappings_path(), &bytes);
+}
+
+/// Write to a temporary file renamed afterwards, so proc macros running in parallel never read a
+/// partially written file
+pub(crate) fn write_atomic(path: &Path, bytes: &[u8]) {
+    let dir = path.parent().unwrap();
+    fs::create_dir_all(dir).unwrap_or_else(|e| panic!("Can't create {:?}: {}", dir, e));
+    let temporary = path.with_extension(format!("tmp{}", std::process::id()));
+    fs::write(&temporary, bytes).unwrap_or_else(|e| panic!("Can't write {:?}: {}", temporary, e));
+    fs::rename(&temporary, path).unwrap();
+}
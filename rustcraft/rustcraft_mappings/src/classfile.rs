@@ -0,0 +1,357 @@
+use crate::parse_java_signature;
+
+pub const ACC_STATIC: u16 = 0x0008;
+pub const ACC_FINAL: u16 = 0x0010;
+pub const ACC_INTERFACE: u16 = 0x0200;
+pub const ACC_ENUM: u16 = 0x4000;
+
+/// Annotations marking a member or a parameter as nullable
+pub const NULLABLE_ANNOTATIONS: [&str; 3] = [
+    "Lorg/jetbrains/annotations/Nullable;",
+    "Ljavax/annotation/Nullable;",
+    "Lorg/jspecify/annotations/Nullable;",
+];
+
+/// Minimal reader for the JVM class file format, used to complete mappings from the game jar
+#[derive(Debug, Clone)]
+pub struct ClassFile {
+    pub access_flags: u16,
+    pub name: String,
+    pub super_class: Option<String>,
+    pub interfaces: Vec<String>,
+    pub fields: Vec<Member>,
+    pub methods: Vec<Member>,
+    /// Generic signature of the class, see `generics::ClassSignature`
+    pub signature: Option<String>,
+}
+
+/// A field or a method of a class file
+#[derive(Debug, Clone)]
+pub struct Member {
+    pub access_flags: u16,
+    pub name: String,
+    pub descriptor: String,
+    /// Descriptors of the annotations of the member, both visible and invisible at runtime
+    pub annotations: Vec<String>,
+    /// Descriptors of the annotations of each parameter of a method, indexed like the parameters
+    /// of its descriptor
+    pub parameter_annotations: Vec<Vec<String>>,
+    /// Generic signature of the member, `None` if its type doesn't involve type parameters
+    pub signature: Option<String>,
+}
+
+impl Member {
+    pub fn is_static(&self) -> bool {
+        self.access_flags & ACC_STATIC != 0
+    }
+
+    pub fn is_final(&self) -> bool {
+        self.access_flags & ACC_FINAL != 0
+    }
+
+    /// Whether the field is a constant of an enum
+    pub fn is_enum(&self) -> bool {
+        self.access_flags & ACC_ENUM != 0
+    }
+
+    pub fn is_nullable(&self) -> bool {
+        is_nullable(&self.annotations)
+    }
+
+    pub fn is_parameter_nullable(&self, index: usize) -> bool {
+        self.parameter_annotations
+            .get(index)
+            .is_some_and(|annotations| is_nullable(annotations))
+    }
+}
+
+fn is_nullable(annotations: &[String]) -> bool {
+    annotations
+        .iter()
+        .any(|annotation| NULLABLE_ANNOTATIONS.contains(&annotation.as_str()))
+}
+
+enum Constant {
+    Utf8(String),
+    Class(u16),
+    Other,
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let result = self.bytes.get(self.position..self.position + len)?;
+        self.position += len;
+        Some(result)
+    }
+    fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|b| b[0])
+    }
+    fn u16(&mut self) -> Option<u16> {
+        self.bytes(2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+    }
+    fn u32(&mut self) -> Option<u32> {
+        self.bytes(4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+}
+
+struct ConstantPool(Vec<Constant>);
+
+impl ConstantPool {
+    fn parse(reader: &mut Reader) -> Option<Self> {
+        let count = reader.u16()?;
+        // Index 0 is unused
+        let mut constants = vec![Constant::Other];
+        while constants.len() < count as usize {
+            let tag = reader.u8()?;
+            let constant = match tag {
+                1 => {
+                    let len = reader.u16()?;
+                    // Modified UTF-8, identical to UTF-8 for names used in class files
+                    Constant::Utf8(String::from_utf8_lossy(reader.bytes(len as usize)?).into())
+                }
+                7 => Constant::Class(reader.u16()?),
+                8 | 16 | 19 | 20 => {
+                    reader.u16()?;
+                    Constant::Other
+                }
+                15 => {
+                    reader.bytes(3)?;
+                    Constant::Other
+                }
+                3 | 4 | 9 | 10 | 11 | 12 | 17 | 18 => {
+                    reader.u32()?;
+                    Constant::Other
+                }
+                5 | 6 => {
+                    reader.bytes(8)?;
+                    // Longs and doubles take two entries
+                    constants.push(Constant::Other);
+                    Constant::Other
+                }
+                _ => return None,
+            };
+            constants.push(constant);
+        }
+        Some(ConstantPool(constants))
+    }
+
+    fn utf8(&self, index: u16) -> Option<&str> {
+        match self.0.get(index as usize)? {
+            Constant::Utf8(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn class(&self, index: u16) -> Option<&str> {
+        match self.0.get(index as usize)? {
+            Constant::Class(name) => self.utf8(*name),
+            _ => None,
+        }
+    }
+}
+
+/// Skip an `element_value` of an annotation
+fn skip_element_value(reader: &mut Reader, constants: &ConstantPool) -> Option<()> {
+    match reader.u8()? {
+        b'B' | b'C' | b'D' | b'F' | b'I' | b'J' | b'S' | b'Z' | b's' | b'c' => {
+            reader.u16()?;
+        }
+        b'e' => {
+            reader.u32()?;
+        }
+        b'@' => {
+            parse_annotation(reader, constants)?;
+        }
+        b'[' => {
+            for _ in 0..reader.u16()? {
+                skip_element_value(reader, constants)?;
+            }
+        }
+        _ => return None,
+    }
+    Some(())
+}
+
+/// Parse an annotation, returning the descriptor of its type
+fn parse_annotation(reader: &mut Reader, constants: &ConstantPool) -> Option<String> {
+    let type_index = reader.u16()?;
+    for _ in 0..reader.u16()? {
+        // Element name
+        reader.u16()?;
+        skip_element_value(reader, constants)?;
+    }
+    Some(constants.utf8(type_index)?.to_string())
+}
+
+fn parse_annotations(reader: &mut Reader, constants: &ConstantPool) -> Option<Vec<String>> {
+    (0..reader.u16()?)
+        .map(|_| parse_annotation(reader, constants))
+        .collect()
+}
+
+fn parse_member(reader: &mut Reader, constants: &ConstantPool) -> Option<Member> {
+    let access_flags = reader.u16()?;
+    let name = constants.utf8(reader.u16()?)?.to_string();
+    let descriptor = constants.utf8(reader.u16()?)?.to_string();
+    let mut member = Member {
+        access_flags,
+        name,
+        descriptor,
+        annotations: vec![],
+        parameter_annotations: vec![],
+        signature: None,
+    };
+    for _ in 0..reader.u16()? {
+        let attribute_name = constants.utf8(reader.u16()?)?;
+        let len = reader.u32()? as usize;
+        let mut attribute = Reader {
+            bytes: reader.bytes(len)?,
+            position: 0,
+        };
+        match attribute_name {
+            "RuntimeVisibleAnnotations" | "RuntimeInvisibleAnnotations" => {
+                member
+                    .annotations
+                    .extend(parse_annotations(&mut attribute, constants)?);
+            }
+            "RuntimeVisibleParameterAnnotations" | "RuntimeInvisibleParameterAnnotations" => {
+                let count = attribute.u8()? as usize;
+                // javac leaves out the first synthetic parameters, like the outer instance taken by
+                // constructors of inner classes, so annotations are those of the last parameters
+                let parameters = parse_java_signature(&member.descriptor)
+                    .map_or(count, |signature| signature.args.len().max(count));
+                if member.parameter_annotations.len() < parameters {
+                    member.parameter_annotations.resize(parameters, vec![]);
+                }
+                for i in parameters - count..parameters {
+                    member.parameter_annotations[i]
+                        .extend(parse_annotations(&mut attribute, constants)?);
+                }
+            }
+            "Signature" => {
+                member.signature = Some(constants.utf8(attribute.u16()?)?.to_string());
+            }
+            _ => {}
+        }
+    }
+    Some(member)
+}
+
+impl ClassFile {
+    pub fn is_final(&self) -> bool {
+        self.access_flags & ACC_FINAL != 0
+    }
+
+    pub fn is_enum(&self) -> bool {
+        self.access_flags & ACC_ENUM != 0
+    }
+
+    pub fn is_interface(&self) -> bool {
+        self.access_flags & ACC_INTERFACE != 0
+    }
+
+    /// Parse a class file, `None` if it is malformed
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader { bytes, position: 0 };
+        if reader.u32()? != 0xCAFEBABE {
+            return None;
+        }
+        // Minor and major versions
+        reader.u32()?;
+        let constants = ConstantPool::parse(&mut reader)?;
+        let access_flags = reader.u16()?;
+        let name = constants.class(reader.u16()?)?.to_string();
+        let super_class = match reader.u16()? {
+            0 => None,
+            index => Some(constants.class(index)?.to_string()),
+        };
+        let interfaces = (0..reader.u16()?)
+            .map(|_| constants.class(reader.u16()?).map(|s| s.to_string()))
+            .collect::<Option<Vec<_>>>()?;
+        let fields = (0..reader.u16()?)
+            .map(|_| parse_member(&mut reader, &constants))
+            .collect::<Option<Vec<_>>>()?;
+        let methods = (0..reader.u16()?)
+            .map(|_| parse_member(&mut reader, &constants))
+            .collect::<Option<Vec<_>>>()?;
+        let mut signature = None;
+        for _ in 0..reader.u16()? {
+            let attribute_name = constants.utf8(reader.u16()?)?;
+            let len = reader.u32()? as usize;
+            let mut attribute = Reader {
+                bytes: reader.bytes(len)?,
+                position: 0,
+            };
+            if attribute_name == "Signature" {
+                signature = Some(constants.utf8(attribute.u16()?)?.to_string());
+            }
+        }
+        Some(ClassFile {
+            access_flags,
+            name,
+            super_class,
+            interfaces,
+            fields,
+            methods,
+            signature,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &[u8] = include_bytes!("../testdata/Fixture.class");
+    const INNER: &[u8] = include_bytes!("../testdata/Fixture$Inner.class");
+
+    fn member<'a>(members: &'a [Member], name: &str) -> &'a Member {
+        members.iter().find(|member| member.name == name).unwrap()
+    }
+
+    #[test]
+    fn fixture() {
+        let class = ClassFile::parse(FIXTURE).unwrap();
+        assert_eq!(class.name, "net/minecraft/Fixture");
+        assert_eq!(class.super_class.as_deref(), Some("java/lang/Object"));
+        assert!(class.is_final() && !class.is_interface() && !class.is_enum());
+        assert_eq!(
+            class.signature.as_deref(),
+            Some("<T::Ljava/lang/Comparable<TT;>;>Ljava/lang/Object;")
+        );
+
+        let constant = member(&class.fields, "CONSTANT");
+        assert!(constant.is_static() && constant.is_final());
+        assert_eq!(constant.signature, None);
+        let value = member(&class.fields, "value");
+        assert!(!value.is_static() && !value.is_final());
+        assert_eq!(value.signature.as_deref(), Some("TT;"));
+
+        let run = member(&class.methods, "run");
+        assert!(run.is_static());
+        assert!(!run.is_parameter_nullable(0));
+        assert!(run.is_parameter_nullable(1));
+
+        assert!(ClassFile::parse(&FIXTURE[..FIXTURE.len() - 1]).is_none());
+    }
+
+    #[test]
+    fn synthetic_parameters() {
+        let class = ClassFile::parse(INNER).unwrap();
+        let constructor = member(&class.methods, "<init>");
+        assert_eq!(
+            constructor.descriptor,
+            "(Lnet/minecraft/Fixture;Ljava/lang/String;I)V"
+        );
+        // The annotations attribute doesn't count the outer instance
+        assert!(!constructor.is_parameter_nullable(0));
+        assert!(constructor.is_parameter_nullable(1));
+        assert!(!constructor.is_parameter_nullable(2));
+    }
+}
@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 
-use crate::{java_to_rust_class, java_to_rust_package, Class, MAPPINGS};
+use crate::{
+    java_to_rust_class, java_to_rust_package, normalize, Class, ClassLookup, Names, Namespace,
+    SigType, MAPPINGS,
+};
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 
@@ -59,7 +62,7 @@ impl Package {
 pub fn auto_gen_impl() -> TokenStream {
     let mut packages = Package::new();
     for class in MAPPINGS.mapped_map.values() {
-        packages.insert(&class.mapped_name, gen_class(class));
+        packages.insert(class.mapped_name(), gen_class(class));
     }
     let result = packages.to_tokens();
     quote! {
@@ -72,47 +75,314 @@ pub fn auto_gen_impl() -> TokenStream {
 fn gen_class(mappings: &Class) -> proc_macro2::TokenStream {
     let struct_name = java_to_rust_class(
         &mappings
-            .mapped_name
+            .mapped_name()
             .rsplit_once("$")
-            .unwrap_or(mappings.mapped_name.rsplit_once("/").unwrap())
+            .unwrap_or(mappings.mapped_name().rsplit_once("/").unwrap())
             .1,
     );
-    let struct_name = format_ident!("{}", struct_name);
+    // Java enums are generated as Rust enums, their objects keeping a wrapper
+    let enum_gen = mappings
+        .is_enum()
+        .then(|| gen_enum(mappings, &format_ident!("{}", struct_name)));
+    let struct_name = match enum_gen {
+        Some(_) => format_ident!("{}Object", struct_name),
+        None => format_ident!("{}", struct_name),
+    };
     let struct_name_interface = format_ident!("{}Interface", struct_name);
 
-    let struct_gen = quote! {
-        #[derive(Debug)]
-        pub struct #struct_name<'a> {
-            pub api: crate::api::ModApi<'a>,
-            pub inner: jni::objects::JObject<'a>
-        }
+    let doc = doc_attributes(&mappings.comments, &[], &mappings.names);
+    let type_parameters = mappings.type_parameters();
+    let type_idents: Vec<_> = type_parameters
+        .iter()
+        .map(|name| format_ident!("{}", name))
+        .collect();
+    // Raw uses of generic classes, like in descriptors, default to any object
+    let (struct_gen, struct_params, struct_args, constructor) = if type_idents.is_empty() {
+        (
+            quote! {
+                #doc
+                #[derive(Debug)]
+                pub struct #struct_name<'a> {
+                    pub api: crate::api::ModApi<'a>,
+                    pub inner: jni::objects::JObject<'a>
+                }
+            },
+            quote!(<'a>),
+            quote!(<'a>),
+            quote!(#struct_name { api, inner }),
+        )
+    } else {
+        (
+            quote! {
+                #doc
+                #[derive(Debug)]
+                pub struct #struct_name<'a, #(#type_idents = crate::java::AnyObject<'a>),*> {
+                    pub api: crate::api::ModApi<'a>,
+                    pub inner: jni::objects::JObject<'a>,
+                    types: std::marker::PhantomData<(#(#type_idents,)*)>
+                }
+            },
+            quote!(<'a, #(#type_idents: crate::object::FromJava<'a>),*>),
+            quote!(<'a, #(#type_idents),*>),
+            quote! {
+                #struct_name {
+                    api,
+                    inner,
+                    types: std::marker::PhantomData,
+                }
+            },
+        )
     };
 
+    let method_names = mappings.method_rust_names();
     let mut methods = vec![];
-    for method in mappings.methods.values() {
-        if mappings.methods_nosig.get(&method.mapped_name) == Some(&"".to_string()) {
-            // TODO Overloading, skip for the moment
-            continue;
-        }
-
-        methods.push(method.to_tokens(mappings.get_java_name(), &struct_name));
+    for (key, method) in mappings.methods.iter() {
+        methods.push(method.to_tokens(
+            mappings.get_java_name(),
+            &method_names[key.as_str()],
+            &type_parameters,
+        ));
     }
 
     for field in mappings.fields.values() {
-        methods.push(field.to_tokens(mappings.get_java_name(), mappings));
+        methods.push(field.to_tokens(mappings.get_java_name(), mappings, &type_parameters));
     }
 
+    let hierarchy = gen_hierarchy(
+        mappings,
+        &struct_name,
+        &struct_name_interface,
+        &struct_params,
+        &struct_args,
+    );
+    let java_name = mappings.get_java_name();
+
     let impl_gen = quote! {
-        impl<'a> #struct_name<'a> {
+        impl #struct_params #struct_name #struct_args {
             pub fn new(api: crate::api::ModApi<'a>, inner: jni::objects::JObject<'a>) -> Self {
-                #struct_name { api, inner }
+                #constructor
             }
             #(#methods)*
         }
     };
 
     quote! {
+        #enum_gen
         #struct_gen
         #impl_gen
+        impl #struct_params crate::object::FromJava<'a> for #struct_name #struct_args {
+            fn from_java(api: crate::api::ModApi<'a>, inner: jni::objects::JObject<'a>) -> Self {
+                #struct_name::new(api, inner)
+            }
+        }
+        impl #struct_params crate::object::ToJava for &#struct_name #struct_args {
+            fn to_java<'local>(self, api: crate::api::ModApi<'local>) -> jni::objects::JObject<'local> {
+                api.new_local_ref(&self.inner)
+            }
+        }
+        impl #struct_params crate::object::JavaObject<'a> for #struct_name #struct_args {
+            fn get_inner(&self) -> &jni::objects::JObject<'_> {
+                &self.inner
+            }
+            fn get_api(&self) -> crate::api::ModApi<'a> {
+                self.api.clone()
+            }
+        }
+        impl #struct_params crate::object::JavaClass<'a> for #struct_name #struct_args {
+            const JAVA_NAME: &'static str = #java_name;
+        }
+        #hierarchy
+    }
+}
+
+/// Trait of the class if it can be extended, and conversions of its wrapper to the wrappers of
+/// its ancestors. Generic ancestors are converted to their raw type
+fn gen_hierarchy(
+    mappings: &Class,
+    struct_name: &proc_macro2::Ident,
+    struct_name_interface: &proc_macro2::Ident,
+    struct_params: &TokenStream,
+    struct_args: &TokenStream,
+) -> TokenStream {
+    let mut result = quote!();
+    if mappings.has_interface_trait() {
+        let doc = format!(
+            " Wrappers of [`{0}`] and of its subclasses, which can be passed where a [`{0}`] is expected",
+            struct_name
+        );
+        result.extend(quote! {
+            #[doc = #doc]
+            pub trait #struct_name_interface<'a>: crate::object::JavaObject<'a> {}
+        });
+    }
+    for ancestor in MAPPINGS.ancestors(mappings) {
+        // Enums are final, only their own wrapper is skipped
+        if ancestor.is_enum() {
+            continue;
+        }
+        let path = SigType::Object(ancestor.mapped_name().to_string()).get_constructor();
+        if !std::ptr::eq(ancestor, mappings) {
+            // Borrowed wrappers are upcast with a new local reference to the same object
+            result.extend(quote! {
+                impl #struct_params From<#struct_name #struct_args> for #path<'a> {
+                    fn from(value: #struct_name #struct_args) -> Self {
+                        #path::new(value.api, value.inner)
+                    }
+                }
+                impl #struct_params From<&#struct_name #struct_args> for #path<'a> {
+                    fn from(value: &#struct_name #struct_args) -> Self {
+                        #path::new(value.api.clone(), value.api.new_local_ref(&value.inner))
+                    }
+                }
+            });
+        }
+        if ancestor.has_interface_trait() {
+            let trait_path =
+                SigType::Object(format!("{}Interface", ancestor.mapped_name())).get_constructor();
+            result.extend(quote! {
+                impl #struct_params #trait_path<'a> for #struct_name #struct_args {}
+            });
+        }
+    }
+    result
+}
+
+/// Rust enum of a java enum, converted to and from its constants
+fn gen_enum(mappings: &Class, enum_name: &proc_macro2::Ident) -> TokenStream {
+    let class_name = mappings.get_java_name();
+    let object_name = format_ident!("{}Object", enum_name);
+    let constants = mappings.enum_constants();
+    let variants: Vec<_> = constants
+        .iter()
+        .map(|(key, _)| {
+            let mut variant = key.to_string();
+            if !normalize(&mut variant, "Variant") && variant == "Self" {
+                variant.insert_str(0, "Variant");
+            }
+            format_ident!("{}", variant)
+        })
+        .collect();
+    let docs = constants
+        .iter()
+        .map(|(_, field)| doc_attributes(&field.comments, &[], &field.names));
+    let field_names: Vec<_> = constants
+        .iter()
+        .map(|(_, field)| field.get_java_name())
+        .collect();
+    let field_type = format!("L{};", class_name);
+    let count = variants.len();
+    let doc = doc_attributes(&mappings.comments, &[], &mappings.names);
+
+    quote! {
+        #doc
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum #enum_name {
+            #(#docs #variants,)*
+        }
+        impl #enum_name {
+            /// Every constant, sorted by intermediary name as the mappings don't give ordinals
+            pub const VALUES: [Self; #count] = [#(Self::#variants),*];
+
+            /// Wrapper of the java object of this constant
+            pub fn object<'a>(self, api: crate::api::ModApi<'a>) -> #object_name<'a> {
+                let inner = crate::object::ToJava::to_java(self, api.clone());
+                #object_name::new(api, inner)
+            }
+        }
+        impl crate::object::ToJava for #enum_name {
+            fn to_java<'local>(self, api: crate::api::ModApi<'local>) -> jni::objects::JObject<'local> {
+                let field: &crate::cache::CachedStaticField = match self {
+                    #(Self::#variants => {
+                        static FIELD: crate::cache::CachedStaticField =
+                            crate::cache::CachedStaticField::new(#class_name, #field_names, #field_type);
+                        &FIELD
+                    })*
+                };
+                api.get_cached_static_field(field).l().unwrap()
+            }
+        }
+        impl crate::object::ToJava for &#enum_name {
+            fn to_java<'local>(self, api: crate::api::ModApi<'local>) -> jni::objects::JObject<'local> {
+                crate::object::ToJava::to_java(*self, api)
+            }
+        }
+        impl<'a> crate::object::FromJava<'a> for #enum_name {
+            fn from_java(api: crate::api::ModApi<'a>, inner: jni::objects::JObject<'a>) -> Self {
+                static ORDINAL: crate::cache::CachedMethod =
+                    crate::cache::CachedMethod::new("java/lang/Enum", "ordinal", "()I");
+                // Ordinals follow the declaration order, which the mappings don't give, so they
+                // are read from the constants on the first conversion
+                static VARIANTS: std::sync::OnceLock<Vec<Option<#enum_name>>> =
+                    std::sync::OnceLock::new();
+                let ordinal = |object: &jni::objects::JObject| {
+                    api.call_cached_method(object, &ORDINAL, &[]).i().unwrap() as usize
+                };
+                let variants = VARIANTS.get_or_init(|| {
+                    let mut variants = vec![None; #count];
+                    for value in Self::VALUES {
+                        let constant = crate::object::ToJava::to_java(value, api.clone());
+                        let index = ordinal(&constant);
+                        api.delete_local_ref(constant);
+                        if index >= variants.len() {
+                            variants.resize(index + 1, None);
+                        }
+                        variants[index] = Some(value);
+                    }
+                    variants
+                });
+                variants
+                    .get(ordinal(&inner))
+                    .copied()
+                    .flatten()
+                    .expect(concat!("Unknown constant of ", #class_name))
+            }
+        }
+    }
+}
+
+/// Markdown of a javadoc comment, inline tags like `{@link Block}` being rendered as code
+pub fn javadoc_to_markdown(javadoc: &str) -> String {
+    // Brackets would be read as intra-doc links
+    let text = |text: &str| text.replace('[', "\\[").replace(']', "\\]");
+    let mut result = String::new();
+    let mut rest = javadoc;
+    while let Some(start) = rest.find("{@") {
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            break;
+        };
+        result += &text(&rest[..start]);
+        let content = rest[start + 2..end]
+            .split_once(char::is_whitespace)
+            .map_or("", |(_, content)| content.trim());
+        result += &format!("`{}`", content);
+        rest = &rest[end + 1..];
+    }
+    result += &text(rest);
+    result.replace("<p>", "\n").replace("</p>", "")
+}
+
+/// Rustdoc of a generated item: its javadoc, extra markdown `sections`, then a note of its
+/// intermediary and yarn names
+pub fn doc_attributes(javadoc: &str, sections: &[String], names: &Names) -> TokenStream {
+    let mut paragraphs = vec![];
+    if !javadoc.trim().is_empty() {
+        paragraphs.push(javadoc_to_markdown(javadoc.trim()));
+    }
+    paragraphs.extend(sections.iter().cloned());
+    paragraphs.push(format!(
+        "Intermediary: `{}`, yarn: `{}`",
+        names.name(Namespace::INTERMEDIARY),
+        names.name(Namespace::NAMED)
+    ));
+    let lines = paragraphs
+        .join("\n\n")
+        .lines()
+        .map(|line| match line.trim_end() {
+            "" => String::new(),
+            line => format!(" {}", line),
+        })
+        .collect::<Vec<_>>();
+    quote! {
+        #(#[doc = #lines])*
     }
 }
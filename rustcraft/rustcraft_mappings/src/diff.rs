@@ -0,0 +1,472 @@
+//! Differences between two sets of mappings, usually of two Minecraft versions. Items are matched
+//! by their intermediary names, which are stable between versions, so renamed yarn names and
+//! changed descriptors can be told apart from added and removed items
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt,
+};
+
+use crate::{json, Class, Field, Mappings, Method, Names, Namespace};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ItemKind {
+    Class,
+    Method,
+    Field,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    /// Named name changed
+    Renamed,
+    /// Intermediary descriptor of a method or field changed
+    DescriptorChanged,
+}
+
+impl ItemKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ItemKind::Class => "class",
+            ItemKind::Method => "method",
+            ItemKind::Field => "field",
+        }
+    }
+}
+
+impl ChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeKind::Added => "added",
+            ChangeKind::Removed => "removed",
+            ChangeKind::Renamed => "renamed",
+            ChangeKind::DescriptorChanged => "descriptor_changed",
+        }
+    }
+}
+
+/// Named names of a changed item in one of the mappings
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct NamedItem {
+    /// Named name of the class, or of the class declaring the member
+    pub class: String,
+    /// Named name of the member, `None` for classes
+    pub member: Option<String>,
+    /// Named descriptor of the member, `None` for classes
+    pub descriptor: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Change {
+    /// Intermediary name of the class, or of the class declaring the member
+    pub class: String,
+    /// Intermediary name of the member, `None` for classes
+    pub member: Option<String>,
+    pub item: ItemKind,
+    pub kind: ChangeKind,
+    /// Item in the old mappings, `None` if added
+    pub old: Option<NamedItem>,
+    /// Item in the new mappings, `None` if removed
+    pub new: Option<NamedItem>,
+}
+
+/// Changes sorted by class and member. Members of added and removed classes aren't listed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MappingsDiff {
+    pub changes: Vec<Change>,
+}
+
+pub fn diff(old: &Mappings, new: &Mappings) -> MappingsDiff {
+    let old_classes = by_intermediary(old.mapped_map.values(), |class| &class.names);
+    let new_classes = by_intermediary(new.mapped_map.values(), |class| &class.names);
+    let mut changes = vec![];
+    for name in union(&old_classes, &new_classes) {
+        let old_class = old_classes.get(name).map(|classes| classes[0]);
+        let new_class = new_classes.get(name).map(|classes| classes[0]);
+        match (old_class, new_class) {
+            (Some(old_class), Some(new_class)) => {
+                let classes = ClassPair {
+                    intermediary: name,
+                    old: old_class,
+                    new: new_class,
+                };
+                classes.diff_class(&mut changes);
+                classes.diff_fields(&mut changes);
+                classes.diff_methods(&mut changes);
+            }
+            (old_class, new_class) => changes.push(Change {
+                class: name.to_string(),
+                member: None,
+                item: ItemKind::Class,
+                kind: match old_class {
+                    Some(_) => ChangeKind::Removed,
+                    None => ChangeKind::Added,
+                },
+                old: old_class.map(class_item),
+                new: new_class.map(class_item),
+            }),
+        }
+    }
+    changes.sort();
+    MappingsDiff { changes }
+}
+
+/// Items by intermediary name, items without one being skipped
+fn by_intermediary<'a, T: 'a>(
+    items: impl Iterator<Item = &'a T>,
+    names: impl Fn(&T) -> &Names,
+) -> HashMap<&'a str, Vec<&'a T>> {
+    let mut result: HashMap<&str, Vec<&T>> = HashMap::new();
+    for item in items {
+        if let Some(name) = names(item).get(Namespace::INTERMEDIARY) {
+            result.entry(name).or_default().push(item);
+        }
+    }
+    result
+}
+
+/// Sorted keys of both maps
+fn union<'a, T>(a: &HashMap<&'a str, T>, b: &HashMap<&'a str, T>) -> BTreeSet<&'a str> {
+    a.keys().chain(b.keys()).copied().collect()
+}
+
+fn class_item(class: &Class) -> NamedItem {
+    NamedItem {
+        class: class.name(Namespace::NAMED).to_string(),
+        member: None,
+        descriptor: None,
+    }
+}
+
+/// Member of a class, as compared by the diff
+#[derive(Clone, Copy)]
+struct Member<'a> {
+    names: &'a Names,
+    descriptors: &'a Names,
+}
+
+impl<'a> Member<'a> {
+    fn of_field(field: &'a Field) -> Self {
+        Member {
+            names: &field.names,
+            descriptors: &field.descriptors,
+        }
+    }
+
+    fn of_method(method: &'a Method) -> Self {
+        Member {
+            names: &method.names,
+            descriptors: &method.descriptors,
+        }
+    }
+
+    fn intermediary_descriptor(&self) -> &'a str {
+        self.descriptors.name(Namespace::INTERMEDIARY)
+    }
+}
+
+fn methods<'a>(methods: &HashMap<&str, Vec<&'a Method>>, name: &str) -> Vec<Member<'a>> {
+    let methods = methods.get(name).into_iter().flatten();
+    methods.map(|method| Member::of_method(method)).collect()
+}
+
+/// A class found in both mappings
+struct ClassPair<'a> {
+    intermediary: &'a str,
+    old: &'a Class,
+    new: &'a Class,
+}
+
+impl ClassPair<'_> {
+    fn item(class: &Class, member: Option<Member>) -> NamedItem {
+        NamedItem {
+            member: member.map(|member| member.names.name(Namespace::NAMED).to_string()),
+            descriptor: member.map(|member| member.descriptors.name(Namespace::NAMED).to_string()),
+            ..class_item(class)
+        }
+    }
+
+    fn change(
+        &self,
+        item: ItemKind,
+        kind: ChangeKind,
+        old: Option<Member>,
+        new: Option<Member>,
+    ) -> Change {
+        let member = old
+            .or(new)
+            .map(|member| member.names.name(Namespace::INTERMEDIARY));
+        Change {
+            class: self.intermediary.to_string(),
+            member: member.map(str::to_string),
+            item,
+            kind,
+            old: (kind != ChangeKind::Added).then(|| Self::item(self.old, old)),
+            new: (kind != ChangeKind::Removed).then(|| Self::item(self.new, new)),
+        }
+    }
+
+    fn diff_class(&self, changes: &mut Vec<Change>) {
+        if self.old.name(Namespace::NAMED) != self.new.name(Namespace::NAMED) {
+            changes.push(self.change(ItemKind::Class, ChangeKind::Renamed, None, None));
+        }
+    }
+
+    fn diff_fields(&self, changes: &mut Vec<Change>) {
+        let old_fields = by_intermediary(self.old.fields.values(), |field| &field.names);
+        let new_fields = by_intermediary(self.new.fields.values(), |field| &field.names);
+        for name in union(&old_fields, &new_fields) {
+            let old = old_fields
+                .get(name)
+                .map(|fields| Member::of_field(fields[0]));
+            let new = new_fields
+                .get(name)
+                .map(|fields| Member::of_field(fields[0]));
+            self.diff_members(changes, ItemKind::Field, old, new);
+        }
+    }
+
+    /// Overloads sharing an intermediary name, like constructors, are matched by descriptor
+    fn diff_methods(&self, changes: &mut Vec<Change>) {
+        let old_methods = by_intermediary(self.old.methods.values(), |method| &method.names);
+        let new_methods = by_intermediary(self.new.methods.values(), |method| &method.names);
+        for name in union(&old_methods, &new_methods) {
+            let (old, mut new) = (methods(&old_methods, name), methods(&new_methods, name));
+            // A single method on both sides is the same one, even if its descriptor changed
+            if let ([old], [new]) = (old.as_slice(), new.as_slice()) {
+                self.diff_members(changes, ItemKind::Method, Some(*old), Some(*new));
+                continue;
+            }
+            for old in old {
+                let position = new
+                    .iter()
+                    .position(|new| new.intermediary_descriptor() == old.intermediary_descriptor());
+                let new = position.map(|i| new.remove(i));
+                self.diff_members(changes, ItemKind::Method, Some(old), new);
+            }
+            for new in new {
+                self.diff_members(changes, ItemKind::Method, None, Some(new));
+            }
+        }
+    }
+
+    fn diff_members(
+        &self,
+        changes: &mut Vec<Change>,
+        item: ItemKind,
+        old: Option<Member>,
+        new: Option<Member>,
+    ) {
+        match (old, new) {
+            (Some(old_member), Some(new_member)) => {
+                if old_member.names.name(Namespace::NAMED)
+                    != new_member.names.name(Namespace::NAMED)
+                {
+                    changes.push(self.change(item, ChangeKind::Renamed, old, new));
+                }
+                if old_member.intermediary_descriptor() != new_member.intermediary_descriptor() {
+                    changes.push(self.change(item, ChangeKind::DescriptorChanged, old, new));
+                }
+            }
+            (Some(_), None) => changes.push(self.change(item, ChangeKind::Removed, old, None)),
+            (None, Some(_)) => changes.push(self.change(item, ChangeKind::Added, None, new)),
+            (None, None) => {}
+        }
+    }
+}
+
+impl fmt::Display for NamedItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.class)?;
+        if let Some(member) = &self.member {
+            write!(f, ".{}", member)?;
+        }
+        match &self.descriptor {
+            Some(descriptor) if descriptor.starts_with('(') => write!(f, "{}", descriptor),
+            Some(descriptor) => write!(f, ": {}", descriptor),
+            None => Ok(()),
+        }
+    }
+}
+
+/// One change per line, like `renamed method net/minecraft/class_2338.method_10263: old -> new`
+impl fmt::Display for MappingsDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in self.changes.iter() {
+            write!(
+                f,
+                "{} {} {}",
+                change.kind.as_str(),
+                change.item.as_str(),
+                change.class
+            )?;
+            if let Some(member) = &change.member {
+                write!(f, ".{}", member)?;
+            }
+            match (&change.old, &change.new) {
+                (Some(old), Some(new)) => writeln!(f, ": {} -> {}", old, new)?,
+                (Some(item), None) | (None, Some(item)) => writeln!(f, ": {}", item)?,
+                (None, None) => writeln!(f)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl MappingsDiff {
+    /// `{"changes": [...]}`, each change having the fields of `Change`, `kind` and `item` being
+    /// written in snake case
+    pub fn to_json(&self) -> String {
+        let item = |item: &Option<NamedItem>| match item {
+            Some(item) => format!(
+                "{{\"class\": {}, \"member\": {}, \"descriptor\": {}}}",
+                json::string(&item.class),
+                json::optional_string(item.member.as_deref()),
+                json::optional_string(item.descriptor.as_deref())
+            ),
+            None => "null".to_string(),
+        };
+        let changes: Vec<String> = self
+            .changes
+            .iter()
+            .map(|change| {
+                format!(
+                    "    {{\"kind\": {}, \"item\": {}, \"class\": {}, \"member\": {}, \"old\": {}, \"new\": {}}}",
+                    json::string(change.kind.as_str()),
+                    json::string(change.item.as_str()),
+                    json::string(&change.class),
+                    json::optional_string(change.member.as_deref()),
+                    item(&change.old),
+                    item(&change.new)
+                )
+            })
+            .collect();
+        if changes.is_empty() {
+            return "{\"changes\": []}".to_string();
+        }
+        format!("{{\"changes\": [\n{}\n]}}", changes.join(",\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tiny::parse_tiny;
+
+    const OLD: &str = "tiny\t2\t0\tofficial\tintermediary\tnamed
+c\ta\tnet/minecraft/class_1\tnet/minecraft/block/Block
+\tf\tI\tb\tfield_1\tluminance
+\tm\t(I)V\tc\tmethod_1\tonSteppedOn
+\tm\t()V\td\tmethod_2\ttick
+c\tb\tnet/minecraft/class_2\tnet/minecraft/util/Removed
+";
+
+    const NEW: &str = "tiny\t2\t0\tofficial\tintermediary\tnamed
+c\ta\tnet/minecraft/class_1\tnet/minecraft/block/BaseBlock
+\tf\tI\tb\tfield_1\tlightLevel
+\tf\tZ\te\tfield_3\tsolid
+\tm\t(J)V\tc\tmethod_1\tonSteppedOn
+c\tf\tnet/minecraft/class_3\tnet/minecraft/util/Added
+";
+
+    fn parse(content: &str) -> Mappings {
+        let mut mappings = Mappings::new();
+        parse_tiny(content, &mut mappings).unwrap();
+        mappings.complete_signatures();
+        mappings
+    }
+
+    #[test]
+    fn changes() {
+        let changes: Vec<(ItemKind, ChangeKind, String)> = diff(&parse(OLD), &parse(NEW))
+            .changes
+            .into_iter()
+            .map(|change| {
+                let name = match change.member {
+                    Some(member) => format!("{}.{}", change.class, member),
+                    None => change.class,
+                };
+                (change.item, change.kind, name)
+            })
+            .collect();
+        let class = "net/minecraft/class_1";
+        assert_eq!(
+            changes,
+            [
+                (ItemKind::Class, ChangeKind::Renamed, class.to_string()),
+                (
+                    ItemKind::Field,
+                    ChangeKind::Renamed,
+                    format!("{}.field_1", class)
+                ),
+                (
+                    ItemKind::Field,
+                    ChangeKind::Added,
+                    format!("{}.field_3", class)
+                ),
+                (
+                    ItemKind::Method,
+                    ChangeKind::DescriptorChanged,
+                    format!("{}.method_1", class)
+                ),
+                (
+                    ItemKind::Method,
+                    ChangeKind::Removed,
+                    format!("{}.method_2", class)
+                ),
+                (
+                    ItemKind::Class,
+                    ChangeKind::Removed,
+                    "net/minecraft/class_2".to_string()
+                ),
+                (
+                    ItemKind::Class,
+                    ChangeKind::Added,
+                    "net/minecraft/class_3".to_string()
+                ),
+            ]
+        );
+        assert_eq!(diff(&parse(OLD), &parse(OLD)), MappingsDiff::default());
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            diff(&parse(OLD), &parse(NEW)).to_string(),
+            "renamed class net/minecraft/class_1: net/minecraft/block/Block -> net/minecraft/block/BaseBlock
+renamed field net/minecraft/class_1.field_1: net/minecraft/block/Block.luminance: I -> net/minecraft/block/BaseBlock.lightLevel: I
+added field net/minecraft/class_1.field_3: net/minecraft/block/BaseBlock.solid: Z
+descriptor_changed method net/minecraft/class_1.method_1: net/minecraft/block/Block.onSteppedOn(I)V -> net/minecraft/block/BaseBlock.onSteppedOn(J)V
+removed method net/minecraft/class_1.method_2: net/minecraft/block/Block.tick()V
+removed class net/minecraft/class_2: net/minecraft/util/Removed
+added class net/minecraft/class_3: net/minecraft/util/Added
+"
+        );
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(MappingsDiff::default().to_json(), "{\"changes\": []}");
+        let diff = MappingsDiff {
+            changes: vec![Change {
+                class: "net/minecraft/class_1".to_string(),
+                member: None,
+                item: ItemKind::Class,
+                kind: ChangeKind::Added,
+                old: None,
+                new: Some(NamedItem {
+                    class: "net/minecraft/\"Quoted\"\\Block".to_string(),
+                    member: None,
+                    descriptor: None,
+                }),
+            }],
+        };
+        assert_eq!(
+            diff.to_json(),
+            r#"{"changes": [
+    {"kind": "added", "item": "class", "class": "net/minecraft/class_1", "member": null, "old": null, "new": {"class": "net/minecraft/\"Quoted\"\\Block", "member": null, "descriptor": null}}
+]}"#
+        );
+    }
+}
@@ -0,0 +1,188 @@
+//! Write mappings as Enigma files, the format of yarn checkouts read by `MappingsSource::Enigma`
+use std::{collections::HashMap, fmt::Write, fs, io, path::Path};
+
+use crate::{Class, Mappings, Namespace};
+
+/// Write one `.mapping` file per top level class in `dir`, at the path of its named name like yarn.
+/// Inner classes are written in the file of their outer class when both are known.
+///
+/// Enigma files only map intermediary names to named ones, other namespaces and the data read
+/// from the game jar are left out
+pub fn write_enigma(mappings: &Mappings, dir: &Path) -> io::Result<()> {
+    let classes = mappings.sorted_classes();
+    let mut inner_classes: HashMap<&str, Vec<&Class>> = HashMap::new();
+    let mut top_level = vec![];
+    for class in classes {
+        if class.names.get(Namespace::INTERMEDIARY).is_none() {
+            continue;
+        }
+        match outer_class(mappings, class) {
+            Some(outer) => inner_classes
+                .entry(outer.intermediary_name())
+                .or_default()
+                .push(class),
+            None => top_level.push(class),
+        }
+    }
+    for class in top_level {
+        let mut content = String::new();
+        write_class(&mut content, class, None, 0, &inner_classes);
+        let path = dir.join(format!("{}.mapping", class.mapped_name()));
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, content)?;
+    }
+    Ok(())
+}
+
+/// Class `class` is nested in, if its names in both namespaces extend the ones of that class, as
+/// `parse_class_inner` prefixes inner class names with their outer ones
+fn outer_class<'a>(mappings: &'a Mappings, class: &Class) -> Option<&'a Class> {
+    let (outer, _) = class.intermediary_name().rsplit_once('$')?;
+    let outer = mappings.translate(outer, Namespace::INTERMEDIARY, Namespace::NAMED)?;
+    let outer = mappings.get_opt(outer)?;
+    let prefix = format!("{}$", outer.mapped_name());
+    class.mapped_name().starts_with(&prefix).then_some(outer)
+}
+
+/// Name of the class in its file, relative to `outer` for inner classes
+fn names_of<'a>(class: &'a Class, outer: Option<&Class>) -> (&'a str, &'a str) {
+    let intermediary = class.intermediary_name();
+    let mapped = class.mapped_name();
+    match outer {
+        Some(outer) => (
+            &intermediary[outer.intermediary_name().len() + 1..],
+            &mapped[outer.mapped_name().len() + 1..],
+        ),
+        None => (intermediary, mapped),
+    }
+}
+
+fn write_class(
+    content: &mut String,
+    class: &Class,
+    outer: Option<&Class>,
+    indent: usize,
+    inner_classes: &HashMap<&str, Vec<&Class>>,
+) {
+    let tabs = "\t".repeat(indent);
+    writeln!(
+        content,
+        "{}CLASS {}",
+        tabs,
+        entry_names(names_of(class, outer))
+    )
+    .unwrap();
+    write_comments(content, indent + 1, &class.comments);
+    for field in class.sorted_fields() {
+        let names = (field.intermediary_name(), field.mapped_name());
+        writeln!(
+            content,
+            "{}\tFIELD {} {}",
+            tabs,
+            entry_names(names),
+            field.descriptor(Namespace::INTERMEDIARY)
+        )
+        .unwrap();
+        write_comments(content, indent + 2, &field.comments);
+    }
+    for method in class.sorted_methods() {
+        let names = (method.intermediary_name(), method.mapped_name());
+        writeln!(
+            content,
+            "{}\tMETHOD {} {}",
+            tabs,
+            entry_names(names),
+            method.descriptor(Namespace::INTERMEDIARY)
+        )
+        .unwrap();
+        write_comments(content, indent + 2, &method.comments);
+        // Enigma arguments always have a name
+        for arg in method.args.iter().filter(|arg| !arg.name.is_empty()) {
+            writeln!(content, "{}\t\tARG {} {}", tabs, arg.position, arg.name).unwrap();
+            write_comments(content, indent + 3, &arg.comment);
+        }
+    }
+    let inner = inner_classes.get(class.intermediary_name());
+    for inner in inner.into_iter().flatten() {
+        write_class(content, inner, Some(class), indent + 1, inner_classes);
+    }
+}
+
+/// Intermediary name, followed by the named one if the entry is mapped
+fn entry_names((intermediary, mapped): (&str, &str)) -> String {
+    if intermediary == mapped {
+        intermediary.to_string()
+    } else {
+        format!("{} {}", intermediary, mapped)
+    }
+}
+
+/// One `COMMENT` line per line of `comments`
+fn write_comments(content: &mut String, indent: usize, comments: &str) {
+    let tabs = "\t".repeat(indent);
+    for line in comments.lines() {
+        let line = format!("{}COMMENT {}", tabs, line);
+        writeln!(content, "{}", line.trim_end()).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        tiny::{parse_tiny, write_tiny},
+        MappingsSource,
+    };
+
+    const TINY: &str = "tiny\t2\t0\tintermediary\tnamed
+c\tnet/minecraft/class_1\tnet/minecraft/block/Block
+\tc\tA block\\nplaced in the world
+\tf\tI\tfield_1\tluminance
+\tm\t(Lnet/minecraft/class_1$class_3;IJ)V\tmethod_1\tonSteppedOn
+\t\tc\tCalled when an entity steps on the block
+\t\tp\t1\t\tsettings
+\t\t\tc\tSettings of the block
+\t\tp\t2\t\t
+\t\tp\t3\t\ttime
+c\tnet/minecraft/class_1$class_3\tnet/minecraft/block/Block$Settings
+c\tnet/minecraft/class_2\tnet/minecraft/class_2
+\tm\t()V\tmethod_2\tmethod_2
+";
+
+    fn parse(content: &str) -> Mappings {
+        let mut mappings = Mappings::new();
+        parse_tiny(content, &mut mappings).unwrap();
+        mappings.complete_signatures();
+        mappings
+    }
+
+    #[test]
+    fn round_trip() {
+        let mappings = parse(TINY);
+        let dir = std::env::temp_dir().join(format!("rustcraft-enigma-{}", std::process::id()));
+        write_enigma(&mappings, &dir).unwrap();
+        let block = fs::read_to_string(dir.join("net/minecraft/block/Block.mapping")).unwrap();
+        let read = MappingsSource::Enigma(dir.clone()).parse_raw();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            block,
+            "CLASS net/minecraft/class_1 net/minecraft/block/Block
+\tCOMMENT A block
+\tCOMMENT placed in the world
+\tFIELD field_1 luminance I
+\tMETHOD method_1 onSteppedOn (Lnet/minecraft/class_1$class_3;IJ)V
+\t\tCOMMENT Called when an entity steps on the block
+\t\tARG 1 settings
+\t\t\tCOMMENT Settings of the block
+\t\tARG 3 time
+\tCLASS class_3 Settings
+"
+        );
+        // Only the nameless argument is lost
+        let expected = TINY.replace("\t\tp\t2\t\t\n", "");
+        assert_eq!(write_tiny(&read.unwrap()), expected);
+        assert_eq!(write_tiny(&mappings), TINY);
+    }
+}
@@ -0,0 +1,285 @@
+//! Errors of lookups in the mappings, suggesting the closest existing names
+use std::{fmt, path::Path};
+
+/// Number of suggestions shown at most
+const MAX_SUGGESTIONS: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MappingsError {
+    UnknownClass {
+        name: String,
+        suggestions: Vec<String>,
+    },
+    /// Method or field missing from a class and its ancestors
+    UnknownMember {
+        class: String,
+        member: String,
+        suggestions: Vec<String>,
+    },
+    /// Method selector matching several overloads, given as `name#descriptor` keys
+    AmbiguousOverload {
+        class: String,
+        method: String,
+        overloads: Vec<String>,
+    },
+    BadDescriptor {
+        descriptor: String,
+        reason: String,
+    },
+    /// Namespace missing from the mappings, needed to complete them
+    MissingNamespace {
+        namespace: String,
+        reason: String,
+    },
+    /// Line of a mappings file which can't be read, `file` being empty until `in_file` is called
+    BadLine {
+        file: String,
+        line: String,
+        reason: String,
+    },
+}
+
+impl MappingsError {
+    pub fn unknown_class<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Self {
+        MappingsError::UnknownClass {
+            name: name.to_string(),
+            suggestions: suggestions(name, candidates),
+        }
+    }
+
+    pub fn unknown_member<'a>(
+        class: &str,
+        member: &str,
+        candidates: impl IntoIterator<Item = &'a str>,
+    ) -> Self {
+        // Selectors are compared on their name only
+        let name = member.split_once('(').map_or(member, |(name, _)| name);
+        MappingsError::UnknownMember {
+            class: class.to_string(),
+            member: member.to_string(),
+            suggestions: suggestions(name, candidates),
+        }
+    }
+
+    pub fn bad_descriptor(descriptor: &str, reason: impl Into<String>) -> Self {
+        MappingsError::BadDescriptor {
+            descriptor: descriptor.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn bad_line(line: &str, reason: impl Into<String>) -> Self {
+        MappingsError::BadLine {
+            file: String::new(),
+            line: line.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Name the file a `BadLine` was read from
+    pub fn in_file(self, path: &Path) -> Self {
+        match self {
+            MappingsError::BadLine { line, reason, .. } => MappingsError::BadLine {
+                file: path.display().to_string(),
+                line,
+                reason,
+            },
+            error => error,
+        }
+    }
+}
+
+impl fmt::Display for MappingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MappingsError::UnknownClass { name, suggestions } => {
+                write!(f, "Can't find class {} in any namespace", name)?;
+                write_suggestions(f, suggestions)
+            }
+            MappingsError::UnknownMember {
+                class,
+                member,
+                suggestions,
+            } => {
+                write!(f, "Can't find {} in {} or its ancestors", member, class)?;
+                write_suggestions(f, suggestions)
+            }
+            MappingsError::AmbiguousOverload {
+                class,
+                method,
+                overloads,
+            } => write!(
+                f,
+                "{} matches several overloads in {}: {}. Add parameter types like `{}(int)` or a descriptor to select one",
+                method,
+                class,
+                overloads.join(", "),
+                method
+            ),
+            MappingsError::BadDescriptor { descriptor, reason } => {
+                write!(f, "Invalid descriptor {}: {}", descriptor, reason)
+            }
+            MappingsError::MissingNamespace { namespace, reason } => {
+                write!(f, "Mappings have no {} names: {}", namespace, reason)
+            }
+            MappingsError::BadLine { file, line, reason } if file.is_empty() => {
+                write!(f, "Invalid mappings line '{}': {}", line, reason)
+            }
+            MappingsError::BadLine { file, line, reason } => {
+                write!(f, "Invalid line '{}' in {}: {}", line, file, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MappingsError {}
+
+fn write_suggestions(f: &mut fmt::Formatter<'_>, suggestions: &[String]) -> fmt::Result {
+    match suggestions {
+        [] => Ok(()),
+        [suggestion] => write!(f, ", did you mean {}?", suggestion),
+        _ => write!(f, ", did you mean one of {}?", suggestions.join(", ")),
+    }
+}
+
+/// Candidates close to `name`, ignoring case, the allowed distance depending on the length of its
+/// simple name. Names without package are compared to the simple names of candidates, so
+/// `BlockPos` suggests `net/minecraft/util/math/BlockPos`
+pub fn suggestions<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let name = name.to_lowercase();
+    let simple_name = name.rsplit('/').next().unwrap();
+    let max_distance = (simple_name.chars().count() / 3).max(1);
+    let mut found: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let compared = if name.contains('/') {
+                candidate
+            } else {
+                candidate.rsplit('/').next().unwrap()
+            };
+            let distance = edit_distance(&name, &compared.to_lowercase());
+            (distance <= max_distance).then_some((distance, candidate))
+        })
+        .collect();
+    found.sort();
+    found.dedup();
+    found
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate.to_string())
+        .collect()
+}
+
+/// Levenshtein distance between the characters of `a` and `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + (a != *b) as usize;
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("getBlock", "getBlock"), 0);
+        assert_eq!(edit_distance("getBlok", "getBlock"), 1);
+    }
+
+    #[test]
+    fn closest_suggestions_first() {
+        let candidates = [
+            "net/minecraft/block/Blocks",
+            "net/minecraft/block/Block",
+            "net/minecraft/util/math/BlockPos",
+            "net/minecraft/block/BlockState",
+            "net/minecraft/item/Item",
+        ];
+        // Simple names are compared without package and case
+        assert_eq!(
+            suggestions("blockpos", candidates),
+            [
+                "net/minecraft/util/math/BlockPos",
+                "net/minecraft/block/Blocks",
+            ]
+        );
+        // Equal distances are sorted by name, and at most `MAX_SUGGESTIONS` are kept
+        assert_eq!(
+            suggestions("Blocks", candidates.iter().copied().chain(["a/Blocks"])),
+            [
+                "a/Blocks",
+                "net/minecraft/block/Blocks",
+                "net/minecraft/block/Block",
+            ]
+        );
+        // Names with a package are compared whole, the distance allowed by the simple name
+        assert_eq!(
+            suggestions("net/minecraft/block/Blok", candidates),
+            ["net/minecraft/block/Block"]
+        );
+        assert!(suggestions("Entity", candidates).is_empty());
+    }
+
+    #[test]
+    fn display() {
+        let error = MappingsError::unknown_class("Blok", ["net/minecraft/block/Block"]);
+        assert_eq!(
+            error.to_string(),
+            "Can't find class Blok in any namespace, did you mean net/minecraft/block/Block?"
+        );
+        assert_eq!(
+            MappingsError::unknown_class("Entity", ["Block"]).to_string(),
+            "Can't find class Entity in any namespace"
+        );
+        let error =
+            MappingsError::unknown_member("Block", "getStat(int)", ["getState", "getStats"]);
+        assert_eq!(
+            error.to_string(),
+            "Can't find getStat(int) in Block or its ancestors, did you mean one of getState, getStats?"
+        );
+        let error = MappingsError::AmbiguousOverload {
+            class: "World".to_string(),
+            method: "setBlockState".to_string(),
+            overloads: vec![
+                "setBlockState#(II)Z".to_string(),
+                "setBlockState#(I)Z".to_string(),
+            ],
+        };
+        assert_eq!(
+            error.to_string(),
+            "setBlockState matches several overloads in World: setBlockState#(II)Z, setBlockState#(I)Z. Add parameter types like `setBlockState(int)` or a descriptor to select one"
+        );
+        assert_eq!(
+            MappingsError::bad_descriptor("(I", "unclosed").to_string(),
+            "Invalid descriptor (I: unclosed"
+        );
+        let error = MappingsError::MissingNamespace {
+            namespace: "official".to_string(),
+            reason: "needed by mojang mappings".to_string(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "Mappings have no official names: needed by mojang mappings"
+        );
+        let error = MappingsError::bad_line("c\ta", "missing names");
+        assert_eq!(
+            error.to_string(),
+            "Invalid mappings line 'c\ta': missing names"
+        );
+        assert_eq!(
+            error.in_file(Path::new("mappings.tiny")).to_string(),
+            "Invalid line 'c\ta' in mappings.tiny: missing names"
+        );
+    }
+}
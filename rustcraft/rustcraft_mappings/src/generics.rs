@@ -0,0 +1,377 @@
+//! Generic signatures of the JVM `Signature` attribute, descriptors being the signatures of
+//! non-generic members
+use std::{
+    fmt::{self, Display},
+    iter::Peekable,
+    str::Chars,
+};
+
+use crate::{parse_type, SigType};
+
+/// Type of a generic signature
+#[derive(Debug, Clone, PartialEq)]
+pub enum GenericType {
+    Primitive(SigType),
+    /// Class with its type arguments. Nested classes are named `Outer$Inner`, arguments of
+    /// their outer classes are dropped
+    Class {
+        name: String,
+        args: Vec<TypeArgument>,
+    },
+    Variable(String),
+    Array(Box<GenericType>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeArgument {
+    /// `?`
+    Wildcard,
+    /// `? extends T`
+    Extends(GenericType),
+    /// `? super T`
+    Super(GenericType),
+    Exact(GenericType),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeParameter {
+    pub name: String,
+    /// `None` when only interfaces bound the parameter, `T::Ljava/lang/Comparable;` leaving the
+    /// class bound empty
+    pub class_bound: Option<GenericType>,
+    pub interface_bounds: Vec<GenericType>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassSignature {
+    pub type_parameters: Vec<TypeParameter>,
+    pub super_class: GenericType,
+    pub interfaces: Vec<GenericType>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodSignature {
+    pub type_parameters: Vec<TypeParameter>,
+    pub args: Vec<GenericType>,
+    pub ret: GenericType,
+    pub throws: Vec<GenericType>,
+}
+
+/// Signatures of classes, methods and fields
+pub trait GenericSignature: Display + Sized {
+    /// Parse a signature, or a descriptor for members. `None` if it is malformed
+    fn parse(s: &str) -> Option<Self>;
+
+    /// Apply `f` to every class name, classes for which it returns `None` are kept as is
+    fn map_classes<'a>(&mut self, f: &impl Fn(&str) -> Option<&'a str>);
+}
+
+type Letters<'a> = Peekable<Chars<'a>>;
+
+fn identifier(letters: &mut Letters, end: &[char]) -> Option<String> {
+    let mut result = String::new();
+    while !end.contains(letters.peek()?) {
+        result.push(letters.next()?);
+    }
+    (!result.is_empty()).then_some(result)
+}
+
+fn type_arguments(letters: &mut Letters) -> Option<Vec<TypeArgument>> {
+    let mut args = vec![];
+    if letters.next_if_eq(&'<').is_none() {
+        return Some(args);
+    }
+    while letters.next_if_eq(&'>').is_none() {
+        args.push(match letters.peek()? {
+            '*' => {
+                letters.next();
+                TypeArgument::Wildcard
+            }
+            '+' => {
+                letters.next();
+                TypeArgument::Extends(GenericType::parse_reference(letters)?)
+            }
+            '-' => {
+                letters.next();
+                TypeArgument::Super(GenericType::parse_reference(letters)?)
+            }
+            _ => TypeArgument::Exact(GenericType::parse_reference(letters)?),
+        });
+    }
+    Some(args)
+}
+
+fn type_parameters(letters: &mut Letters) -> Option<Vec<TypeParameter>> {
+    let mut parameters = vec![];
+    if letters.next_if_eq(&'<').is_none() {
+        return Some(parameters);
+    }
+    while letters.next_if_eq(&'>').is_none() {
+        let name = identifier(letters, &[':'])?;
+        letters.next_if_eq(&':')?;
+        // The class bound is empty when there are only interface bounds
+        let class_bound = match letters.peek()? {
+            ':' => None,
+            _ => Some(GenericType::parse_reference(letters)?),
+        };
+        let mut interface_bounds = vec![];
+        while letters.next_if_eq(&':').is_some() {
+            interface_bounds.push(GenericType::parse_reference(letters)?);
+        }
+        parameters.push(TypeParameter {
+            name,
+            class_bound,
+            interface_bounds,
+        });
+    }
+    Some(parameters)
+}
+
+impl GenericType {
+    fn parse_type(letters: &mut Letters) -> Option<Self> {
+        match letters.peek()? {
+            'L' | 'T' | '[' => Self::parse_reference(letters),
+            'Z' | 'B' | 'C' | 'S' | 'I' | 'J' | 'F' | 'D' | 'V' => {
+                Some(GenericType::Primitive(parse_type(letters)?))
+            }
+            _ => None,
+        }
+    }
+
+    fn parse_reference(letters: &mut Letters) -> Option<Self> {
+        match letters.next()? {
+            'L' => {
+                let mut name = identifier(letters, &['<', '.', ';'])?;
+                let mut args = type_arguments(letters)?;
+                while letters.next_if_eq(&'.').is_some() {
+                    name.push('$');
+                    name += &identifier(letters, &['<', '.', ';'])?;
+                    args = type_arguments(letters)?;
+                }
+                letters.next_if_eq(&';')?;
+                Some(GenericType::Class { name, args })
+            }
+            'T' => {
+                let name = identifier(letters, &[';'])?;
+                letters.next();
+                Some(GenericType::Variable(name))
+            }
+            '[' => Some(GenericType::Array(Box::new(Self::parse_type(letters)?))),
+            _ => None,
+        }
+    }
+
+    /// Replace type variables missing from `kept` by `Object`
+    pub fn erase_variables(&mut self, kept: &[String]) {
+        match self {
+            GenericType::Variable(name) if !kept.contains(name) => {
+                *self = GenericType::Class {
+                    name: "java/lang/Object".to_string(),
+                    args: vec![],
+                }
+            }
+            GenericType::Class { args, .. } => {
+                for arg in args.iter_mut() {
+                    match arg {
+                        TypeArgument::Wildcard => {}
+                        TypeArgument::Extends(t)
+                        | TypeArgument::Super(t)
+                        | TypeArgument::Exact(t) => t.erase_variables(kept),
+                    }
+                }
+            }
+            GenericType::Array(t) => t.erase_variables(kept),
+            GenericType::Primitive(_) | GenericType::Variable(_) => {}
+        }
+    }
+}
+
+impl GenericSignature for GenericType {
+    fn parse(s: &str) -> Option<Self> {
+        let mut letters = s.chars().peekable();
+        let result = Self::parse_type(&mut letters)?;
+        letters.peek().is_none().then_some(result)
+    }
+
+    fn map_classes<'a>(&mut self, f: &impl Fn(&str) -> Option<&'a str>) {
+        match self {
+            GenericType::Class { name, args } => {
+                if let Some(mapped) = f(name) {
+                    *name = mapped.to_string();
+                }
+                for arg in args.iter_mut() {
+                    match arg {
+                        TypeArgument::Wildcard => {}
+                        TypeArgument::Extends(t)
+                        | TypeArgument::Super(t)
+                        | TypeArgument::Exact(t) => t.map_classes(f),
+                    }
+                }
+            }
+            GenericType::Array(t) => t.map_classes(f),
+            GenericType::Primitive(_) | GenericType::Variable(_) => {}
+        }
+    }
+}
+
+fn map_parameters<'a>(parameters: &mut [TypeParameter], f: &impl Fn(&str) -> Option<&'a str>) {
+    let bounds = parameters.iter_mut().flat_map(|p| {
+        p.class_bound
+            .iter_mut()
+            .chain(p.interface_bounds.iter_mut())
+    });
+    for bound in bounds {
+        bound.map_classes(f);
+    }
+}
+
+impl GenericSignature for ClassSignature {
+    fn parse(s: &str) -> Option<Self> {
+        let mut letters = s.chars().peekable();
+        let type_parameters = type_parameters(&mut letters)?;
+        let super_class = GenericType::parse_reference(&mut letters)?;
+        let mut interfaces = vec![];
+        while letters.peek().is_some() {
+            interfaces.push(GenericType::parse_reference(&mut letters)?);
+        }
+        Some(ClassSignature {
+            type_parameters,
+            super_class,
+            interfaces,
+        })
+    }
+
+    fn map_classes<'a>(&mut self, f: &impl Fn(&str) -> Option<&'a str>) {
+        map_parameters(&mut self.type_parameters, f);
+        self.super_class.map_classes(f);
+        for interface in self.interfaces.iter_mut() {
+            interface.map_classes(f);
+        }
+    }
+}
+
+impl GenericSignature for MethodSignature {
+    fn parse(s: &str) -> Option<Self> {
+        let mut letters = s.chars().peekable();
+        let type_parameters = type_parameters(&mut letters)?;
+        letters.next_if_eq(&'(')?;
+        let mut args = vec![];
+        while letters.next_if_eq(&')').is_none() {
+            args.push(GenericType::parse_type(&mut letters)?);
+        }
+        let ret = GenericType::parse_type(&mut letters)?;
+        let mut throws = vec![];
+        while letters.next_if_eq(&'^').is_some() {
+            throws.push(GenericType::parse_reference(&mut letters)?);
+        }
+        letters.peek().is_none().then_some(MethodSignature {
+            type_parameters,
+            args,
+            ret,
+            throws,
+        })
+    }
+
+    fn map_classes<'a>(&mut self, f: &impl Fn(&str) -> Option<&'a str>) {
+        map_parameters(&mut self.type_parameters, f);
+        for t in self
+            .args
+            .iter_mut()
+            .chain(std::iter::once(&mut self.ret))
+            .chain(self.throws.iter_mut())
+        {
+            t.map_classes(f);
+        }
+    }
+}
+
+impl Display for GenericType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenericType::Primitive(t) => write!(f, "{}", t.jni_name().to_ascii_uppercase()),
+            GenericType::Class { name, args } => {
+                write!(f, "L{}", name)?;
+                if !args.is_empty() {
+                    write!(f, "<")?;
+                    for arg in args {
+                        match arg {
+                            TypeArgument::Wildcard => write!(f, "*")?,
+                            TypeArgument::Extends(t) => write!(f, "+{}", t)?,
+                            TypeArgument::Super(t) => write!(f, "-{}", t)?,
+                            TypeArgument::Exact(t) => write!(f, "{}", t)?,
+                        }
+                    }
+                    write!(f, ">")?;
+                }
+                write!(f, ";")
+            }
+            GenericType::Variable(name) => write!(f, "T{};", name),
+            GenericType::Array(t) => write!(f, "[{}", t),
+        }
+    }
+}
+
+fn fmt_parameters(parameters: &[TypeParameter], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if parameters.is_empty() {
+        return Ok(());
+    }
+    write!(f, "<")?;
+    for parameter in parameters {
+        write!(f, "{}:", parameter.name)?;
+        if let Some(bound) = &parameter.class_bound {
+            write!(f, "{}", bound)?;
+        }
+        for bound in parameter.interface_bounds.iter() {
+            write!(f, ":{}", bound)?;
+        }
+    }
+    write!(f, ">")
+}
+
+impl Display for ClassSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_parameters(&self.type_parameters, f)?;
+        write!(f, "{}", self.super_class)?;
+        for interface in self.interfaces.iter() {
+            write!(f, "{}", interface)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for MethodSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_parameters(&self.type_parameters, f)?;
+        write!(f, "(")?;
+        for arg in self.args.iter() {
+            write!(f, "{}", arg)?;
+        }
+        write!(f, "){}", self.ret)?;
+        for throw in self.throws.iter() {
+            write!(f, "^{}", throw)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn type_parameter_bounds() {
+        for signature in [
+            "<T:Ljava/lang/Object;>Ljava/lang/Object;",
+            "<T::Ljava/lang/Comparable<TT;>;>Ljava/lang/Object;",
+            "<T:Ljava/lang/Number;:Ljava/lang/Comparable<TT;>;>Ljava/lang/Object;",
+        ] {
+            let parsed = ClassSignature::parse(signature).unwrap();
+            assert_eq!(parsed.to_string(), signature);
+        }
+        let signature = "<T::Ljava/lang/Comparable<TT;>;>(TT;)V";
+        assert_eq!(
+            MethodSignature::parse(signature).unwrap().to_string(),
+            signature
+        );
+    }
+}
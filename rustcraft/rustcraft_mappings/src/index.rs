@@ -0,0 +1,269 @@
+//! Compact index of the mappings, read by proc macros instead of the whole cache. Names are
+//! searched in sorted tables without decoding anything, and each class is encoded on its own so a
+//! lookup only decodes the classes it reaches.
+//!
+//! Layout, integers being little endian `u32`:
+//! - `RCMI` magic and `VERSION`
+//! - name and class counts
+//! - name entries sorted by name: offset and length of the name, index of its class
+//! - class entries: offset and length of the bitcode of the class
+//! - names, then encoded classes
+//!
+//! Index files are mapped in memory rather than read, as a lookup only touches a few pages.
+use std::{cmp::Ordering, collections::HashMap, fs::File, ops::Deref, path::Path, sync::OnceLock};
+
+use memmap2::Mmap;
+
+use crate::{cache, Class, ClassLookup, Mappings};
+
+const MAGIC: &[u8; 4] = b"RCMI";
+/// Bumped when the layout changes, changes of `Class` being covered by the cache key
+const VERSION: u32 = 1;
+const HEADER_LEN: usize = 16;
+const NAME_ENTRY_LEN: usize = 12;
+const CLASS_ENTRY_LEN: usize = 8;
+
+/// Bytes of an index, mapped from its file or encoded in memory
+enum Bytes {
+    Mapped(Mmap),
+    Owned(Vec<u8>),
+}
+
+impl Deref for Bytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Bytes::Mapped(map) => map,
+            Bytes::Owned(bytes) => bytes,
+        }
+    }
+}
+
+pub struct MappingsIndex {
+    bytes: Bytes,
+    name_count: usize,
+    classes: Vec<OnceLock<Class>>,
+}
+
+impl MappingsIndex {
+    /// Index every class of `mappings` by its names in every namespace
+    pub fn encode(mappings: &Mappings) -> Vec<u8> {
+        let mut classes: Vec<&Class> = mappings.mapped_map.values().collect();
+        classes.sort_by_key(|class| class.mapped_name());
+        let positions: HashMap<&str, u32> = classes
+            .iter()
+            .enumerate()
+            .map(|(i, class)| (class.mapped_name(), i as u32))
+            .collect();
+        // Same priority as `Mappings::get_opt`, named names then namespaces in order
+        let mut names: HashMap<&str, u32> = positions.clone();
+        for class_names in mappings.class_names.iter() {
+            for (name, mapped_name) in class_names.iter() {
+                names.entry(name).or_insert(positions[mapped_name.as_str()]);
+            }
+        }
+        let mut names: Vec<(&str, u32)> = names.into_iter().collect();
+        names.sort();
+        let encoded: Vec<Vec<u8>> = classes
+            .iter()
+            .map(|class| bitcode::encode(*class))
+            .collect();
+
+        let mut offset =
+            HEADER_LEN + names.len() * NAME_ENTRY_LEN + classes.len() * CLASS_ENTRY_LEN;
+        let mut bytes = Vec::with_capacity(offset);
+        bytes.extend(MAGIC);
+        for value in [VERSION, names.len() as u32, classes.len() as u32] {
+            bytes.extend(value.to_le_bytes());
+        }
+        for (name, class) in names.iter() {
+            for value in [offset as u32, name.len() as u32, *class] {
+                bytes.extend(value.to_le_bytes());
+            }
+            offset += name.len();
+        }
+        for class in encoded.iter() {
+            for value in [offset as u32, class.len() as u32] {
+                bytes.extend(value.to_le_bytes());
+            }
+            offset += class.len();
+        }
+        for (name, _) in names.iter() {
+            bytes.extend(name.as_bytes());
+        }
+        for class in encoded.iter() {
+            bytes.extend(class);
+        }
+        bytes
+    }
+
+    /// `None` if `bytes` isn't an index of the current version
+    pub fn from_bytes(bytes: Vec<u8>) -> Option<Self> {
+        Self::new(Bytes::Owned(bytes))
+    }
+
+    fn new(bytes: Bytes) -> Option<Self> {
+        if bytes.get(..4)? != MAGIC {
+            return None;
+        }
+        let mut index = MappingsIndex {
+            bytes,
+            name_count: 0,
+            classes: vec![],
+        };
+        if index.u32_at(4)? != VERSION {
+            return None;
+        }
+        index.name_count = index.u32_at(8)? as usize;
+        let class_count = index.u32_at(12)? as usize;
+        let len = HEADER_LEN + index.name_count * NAME_ENTRY_LEN + class_count * CLASS_ENTRY_LEN;
+        if index.bytes.len() < len {
+            return None;
+        }
+        index.classes = (0..class_count).map(|_| OnceLock::new()).collect();
+        Some(index)
+    }
+
+    /// `None` if the file is missing or isn't an index of the current version
+    pub fn read(path: &Path) -> Option<Self> {
+        let file = File::open(path).ok()?;
+        // SAFETY: index files are only replaced by renaming a new file over them, never written in
+        // place, so the mapped file doesn't change
+        let map = unsafe { Mmap::map(&file) }.ok()?;
+        Self::new(Bytes::Mapped(map))
+    }
+
+    pub fn write(mappings: &Mappings, path: &Path) {
+        cache::write_atomic(path, &Self::encode(mappings));
+    }
+
+    fn u32_at(&self, offset: usize) -> Option<u32> {
+        Some(u32::from_le_bytes(
+            self.bytes.get(offset..offset + 4)?.try_into().unwrap(),
+        ))
+    }
+
+    /// Bytes of an `(offset, length)` entry
+    fn slice_at(&self, entry: usize) -> &[u8] {
+        let offset = self.u32_at(entry).unwrap() as usize;
+        let len = self.u32_at(entry + 4).unwrap() as usize;
+        &self.bytes[offset..offset + len]
+    }
+
+    fn name_entry(&self, i: usize) -> (&[u8], usize) {
+        let entry = HEADER_LEN + i * NAME_ENTRY_LEN;
+        (
+            self.slice_at(entry),
+            self.u32_at(entry + 8).unwrap() as usize,
+        )
+    }
+
+    fn class(&self, i: usize) -> &Class {
+        self.classes[i].get_or_init(|| {
+            let entry = HEADER_LEN + self.name_count * NAME_ENTRY_LEN + i * CLASS_ENTRY_LEN;
+            bitcode::decode(self.slice_at(entry))
+                .expect("Mappings index doesn't match this version of rustcraft_mappings")
+        })
+    }
+}
+
+impl ClassLookup for MappingsIndex {
+    fn get_opt(&self, name: &str) -> Option<&Class> {
+        let (mut low, mut high) = (0, self.name_count);
+        while low < high {
+            let middle = (low + high) / 2;
+            let (entry_name, class) = self.name_entry(middle);
+            match entry_name.cmp(name.as_bytes()) {
+                Ordering::Less => low = middle + 1,
+                Ordering::Greater => high = middle,
+                Ordering::Equal => return Some(self.class(class)),
+            }
+        }
+        None
+    }
+
+    fn class_names(&self) -> Vec<&str> {
+        (0..self.name_count)
+            .map(|i| std::str::from_utf8(self.name_entry(i).0).unwrap())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tiny::parse_tiny, Namespace};
+
+    const TINY: &str = "tiny\t2\t0\tofficial\tintermediary\tnamed\tmojang
+c\ta\tnet/minecraft/class_1\tnet/minecraft/block/Block\tnet/minecraft/world/level/block/Block
+\tf\tI\tb\tfield_1\tluminance\tlightEmission
+\tm\t(Lb;)V\tc\tmethod_1\tonSteppedOn\tstepOn
+c\tb\tnet/minecraft/class_2\tnet/minecraft/util/math/BlockPos\tnet/minecraft/core/BlockPos
+";
+
+    fn index() -> MappingsIndex {
+        let mut mappings = Mappings::new();
+        parse_tiny(TINY, &mut mappings).unwrap();
+        mappings.complete_signatures();
+        MappingsIndex::from_bytes(MappingsIndex::encode(&mappings)).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let index = index();
+        let mut names = index.class_names();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "a",
+                "b",
+                "net/minecraft/block/Block",
+                "net/minecraft/class_1",
+                "net/minecraft/class_2",
+                "net/minecraft/core/BlockPos",
+                "net/minecraft/util/math/BlockPos",
+                "net/minecraft/world/level/block/Block",
+            ]
+        );
+        let class = index.get_opt("net/minecraft/block/Block").unwrap();
+        let method = class.methods.values().next().unwrap();
+        assert_eq!(
+            method.descriptor(Namespace::NAMED),
+            "(Lnet/minecraft/util/math/BlockPos;)V"
+        );
+        assert!(index.get_opt("net/minecraft/class_3").is_none());
+    }
+
+    #[test]
+    fn rejects_other_bytes() {
+        let mut bytes = MappingsIndex::encode(&Mappings::new());
+        assert!(MappingsIndex::from_bytes(bytes.clone()).is_some());
+        bytes[4] += 1;
+        assert!(MappingsIndex::from_bytes(bytes.clone()).is_none());
+        assert!(MappingsIndex::from_bytes(bytes[..HEADER_LEN - 1].to_vec()).is_none());
+        assert!(MappingsIndex::from_bytes(b"PK\x03\x04".to_vec()).is_none());
+    }
+
+    #[test]
+    fn lookup_in_every_namespace() {
+        let index = index();
+        let classes = [
+            "a",
+            "net/minecraft/class_1",
+            "net/minecraft/block/Block",
+            "net/minecraft/world/level/block/Block",
+        ];
+        let fields = ["b", "field_1", "luminance", "lightEmission"];
+        let methods = ["c", "method_1", "onSteppedOn", "stepOn"];
+        for ((class, field), method) in classes.into_iter().zip(fields).zip(methods) {
+            let class = ClassLookup::class(&index, class).unwrap();
+            assert_eq!(class.mapped_name(), "net/minecraft/block/Block");
+            let field = index.field(class, field).unwrap();
+            assert_eq!(field.intermediary_name(), "field_1");
+            let method = index.method(class, method).unwrap();
+            assert_eq!(method.intermediary_name(), "method_1");
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    env, fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use zip::ZipArchive;
+
+use crate::classfile::ClassFile;
+
+/// Path of the tiny file inside yarn and intermediary jars
+pub const TINY_ENTRY: &str = "mappings/mappings.tiny";
+
+/// Read the tiny mappings shipped in a yarn or intermediary jar, without extracting it on disk
+pub fn read_tiny_from_jar(jar: &Path) -> String {
+    let file = fs::File::open(jar).unwrap_or_else(|e| panic!("Can't open jar {:?}: {}", jar, e));
+    let mut archive =
+        ZipArchive::new(file).unwrap_or_else(|e| panic!("Invalid jar {:?}: {}", jar, e));
+    let mut entry = archive
+        .by_name(TINY_ENTRY)
+        .unwrap_or_else(|e| panic!("Can't find {} in {:?}: {}", TINY_ENTRY, jar, e));
+    let mut content = String::new();
+    entry.read_to_string(&mut content).unwrap();
+    content
+}
+
+/// Parse every class of a jar, malformed ones are skipped
+pub fn read_classes(jar: &Path) -> Vec<ClassFile> {
+    let file = fs::File::open(jar).unwrap_or_else(|e| panic!("Can't open jar {:?}: {}", jar, e));
+    let mut archive =
+        ZipArchive::new(file).unwrap_or_else(|e| panic!("Invalid jar {:?}: {}", jar, e));
+    let mut classes = vec![];
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).unwrap();
+        if !entry.name().ends_with(".class") {
+            continue;
+        }
+        let mut bytes = vec![];
+        entry.read_to_end(&mut bytes).unwrap();
+        classes.extend(ClassFile::parse(&bytes));
+    }
+    classes
+}
+
+/// Versions used by the fabric mod, read from its `gradle.properties`
+#[derive(Debug, Default)]
+pub struct GradleVersions {
+    pub minecraft_version: Option<String>,
+    pub yarn_mappings: Option<String>,
+}
+
+impl GradleVersions {
+    /// `RUSTCRAFT_GRADLE_PROPERTIES` if set, `rustCraftMod/gradle.properties` of this repository otherwise
+    pub fn load() -> Self {
+        let path = env::var("RUSTCRAFT_GRADLE_PROPERTIES")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| {
+                Path::new(env!("CARGO_MANIFEST_DIR")).join("../../rustCraftMod/gradle.properties")
+            });
+        fs::read_to_string(path)
+            .map(|content| Self::parse(&content))
+            .unwrap_or_default()
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let mut versions = Self::default();
+        for line in content.lines() {
+            if let Some((key, value)) = line.split_once('=') {
+                let value = Some(value.trim().to_string());
+                match key.trim() {
+                    "minecraft_version" => versions.minecraft_version = value,
+                    "yarn_mappings" => versions.yarn_mappings = value,
+                    _ => {}
+                }
+            }
+        }
+        versions
+    }
+}
+
+/// `GRADLE_USER_HOME`, or `.gradle` in the home directory, `USERPROFILE` on Windows
+fn gradle_home() -> Option<PathBuf> {
+    env::var("GRADLE_USER_HOME")
+        .map(PathBuf::from)
+        .ok()
+        .or_else(|| {
+            env::var("HOME")
+                .or_else(|_| env::var("USERPROFILE"))
+                .ok()
+                .map(|home| Path::new(&home).join(".gradle"))
+        })
+}
+
+/// Find `<artifact>-<version>-v2.jar` in the gradle dependency cache, where loom downloads it
+fn find_cached_artifact(gradle_home: &Path, artifact: &str, version: &str) -> Option<PathBuf> {
+    let version_dir = gradle_home
+        .join("caches/modules-2/files-2.1/net.fabricmc")
+        .join(artifact)
+        .join(version);
+    let jar_name = format!("{}-{}-v2.jar", artifact, version);
+    // Each file is stored in a directory named after its hash
+    fs::read_dir(version_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path().join(&jar_name))
+        .find(|path| path.is_file())
+}
+
+/// Look for the yarn jar, or the intermediary one if yarn wasn't downloaded, in the gradle cache
+pub fn find_mappings_jar() -> Option<PathBuf> {
+    let gradle_home = gradle_home()?;
+    let versions = GradleVersions::load();
+    versions
+        .yarn_mappings
+        .and_then(|version| find_cached_artifact(&gradle_home, "yarn", &version))
+        .or_else(|| {
+            versions
+                .minecraft_version
+                .and_then(|version| find_cached_artifact(&gradle_home, "intermediary", &version))
+        })
+}
+
+/// `RUSTCRAFT_MINECRAFT_JAR` if set, otherwise the game jar extracted by loom in the gradle cache,
+/// remapped ones being preferred
+pub fn find_minecraft_jar() -> Option<PathBuf> {
+    if let Ok(path) = env::var("RUSTCRAFT_MINECRAFT_JAR") {
+        return Some(PathBuf::from(path));
+    }
+    let version = GradleVersions::load().minecraft_version?;
+    let loom_dir = gradle_home()?.join("caches/fabric-loom").join(version);
+    let mut jars: Vec<PathBuf> = fs::read_dir(loom_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("minecraft-") && name.ends_with(".jar"))
+        })
+        .collect();
+    let priority = |path: &PathBuf| {
+        let name = path.file_name().unwrap().to_string_lossy();
+        ["intermediary", "merged", "client"]
+            .iter()
+            .position(|kind| name.contains(kind))
+            .unwrap_or(usize::MAX)
+    };
+    jars.sort_by_key(|path| (priority(path), path.clone()));
+    jars.into_iter().next()
+}
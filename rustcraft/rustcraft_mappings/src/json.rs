@@ -0,0 +1,208 @@
+//! Write mappings as JSON, by hand as the crate doesn't depend on serde. `write_json` follows this
+//! schema, names being keyed by namespace and unknown ones left out:
+//!
+//! ```text
+//! {
+//!   "namespaces": ["official", "intermediary", "named", "mojang"],
+//!   "classes": [{
+//!     "names": {"official": "dcs", "intermediary": "net/minecraft/class_2248", "named": "net/minecraft/block/Block"},
+//!     "super_class": "net/minecraft/class_4970" | null,   // intermediary name
+//!     "interfaces": ["net/minecraft/class_1935"],         // intermediary names
+//!     "signatures": {"named": "<T:...>..."},              // generic signatures, empty if none
+//!     "comment": "",
+//!     "modifiers": ["static" | "nullable" | "final" | "enum" | "interface"],
+//!     "modifiers_known": false,                           // modifiers of the class and members were read
+//!     "fields": [{"names": {...}, "descriptors": {...}, "signatures": {...}, "comment": "", "modifiers": [...]}],
+//!     "methods": [{
+//!       "names": {...}, "descriptors": {...}, "signatures": {...}, "comment": "", "modifiers": [...],
+//!       "args": [{"position": 1, "name": "world", "comment": "", "modifiers": [...]}]  // position is the local variable slot
+//!     }]
+//!   }]
+//! }
+//! ```
+//!
+//! Classes are sorted by intermediary name, members by intermediary name and descriptor
+use std::fmt::Write;
+
+use crate::{Arg, Class, Field, Mappings, Method, Modifier, Names, Namespace};
+
+/// `s` as a quoted and escaped JSON string
+pub(crate) fn string(s: &str) -> String {
+    let mut result = String::with_capacity(s.len() + 2);
+    result.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if c < ' ' => write!(result, "\\u{:04x}", c as u32).unwrap(),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+/// `value` as a JSON string, or `null`
+pub(crate) fn optional_string(value: Option<&str>) -> String {
+    value.map_or_else(|| "null".to_string(), string)
+}
+
+/// Items of an array one per line, indented by `indent` spaces
+fn array(items: Vec<String>, indent: usize) -> String {
+    if items.is_empty() {
+        return "[]".to_string();
+    }
+    let separator = format!(",\n{}", " ".repeat(indent));
+    format!(
+        "[\n{}{}\n{}]",
+        " ".repeat(indent),
+        items.join(&separator),
+        " ".repeat(indent - 2)
+    )
+}
+
+fn strings<'a>(items: impl IntoIterator<Item = &'a str>) -> String {
+    let items: Vec<String> = items.into_iter().map(string).collect();
+    format!("[{}]", items.join(", "))
+}
+
+/// Mappings of the JSON schema, see the module documentation
+pub fn write_json(mappings: &Mappings) -> String {
+    let writer = Writer { mappings };
+    let classes = mappings
+        .sorted_classes()
+        .into_iter()
+        .map(|class| writer.class(class))
+        .collect();
+    format!(
+        "{{\"namespaces\": {}, \"classes\": {}}}",
+        strings(mappings.namespaces.iter().map(String::as_str)),
+        array(classes, 2)
+    )
+}
+
+struct Writer<'a> {
+    mappings: &'a Mappings,
+}
+
+impl Writer<'_> {
+    fn names(&self, names: &Names) -> String {
+        let names: Vec<String> = names
+            .iter()
+            .map(|(ns, name)| self.entry(ns, name))
+            .collect();
+        format!("{{{}}}", names.join(", "))
+    }
+
+    fn entry(&self, ns: Namespace, name: &str) -> String {
+        let namespace = self
+            .mappings
+            .namespaces
+            .get(ns.index())
+            .map_or_else(|| ns.index().to_string(), String::clone);
+        format!("{}: {}", string(&namespace), string(name))
+    }
+
+    /// Keys shared by classes and members
+    fn common(&self, names: &Names, comment: &str, modifiers: u8) -> String {
+        format!(
+            "\"names\": {}, \"comment\": {}, \"modifiers\": {}",
+            self.names(names),
+            string(comment),
+            strings(Modifier::names(modifiers))
+        )
+    }
+
+    fn class(&self, class: &Class) -> String {
+        let fields = class
+            .sorted_fields()
+            .into_iter()
+            .map(|field| self.field(field))
+            .collect();
+        let methods = class
+            .sorted_methods()
+            .into_iter()
+            .map(|method| self.method(method))
+            .collect();
+        format!(
+            "{{{}, \"modifiers_known\": {}, \"super_class\": {}, \"interfaces\": {}, \"signatures\": {}, \"fields\": {}, \"methods\": {}}}",
+            self.common(&class.names, &class.comments, class.modifiers),
+            class.modifiers_known,
+            optional_string(class.super_class.as_deref()),
+            strings(class.interfaces.iter().map(String::as_str)),
+            self.names(&class.signatures),
+            array(fields, 4),
+            array(methods, 4)
+        )
+    }
+
+    fn field(&self, field: &Field) -> String {
+        format!(
+            "{{{}, \"descriptors\": {}, \"signatures\": {}}}",
+            self.common(&field.names, &field.comments, field.modifiers),
+            self.names(&field.descriptors),
+            self.names(&field.signatures)
+        )
+    }
+
+    fn method(&self, method: &Method) -> String {
+        let args: Vec<String> = method.args.iter().map(arg).collect();
+        format!(
+            "{{{}, \"descriptors\": {}, \"signatures\": {}, \"args\": [{}]}}",
+            self.common(&method.names, &method.comments, method.modifiers),
+            self.names(&method.descriptors),
+            self.names(&method.signatures),
+            args.join(", ")
+        )
+    }
+}
+
+fn arg(arg: &Arg) -> String {
+    format!(
+        "{{\"position\": {}, \"name\": {}, \"comment\": {}, \"modifiers\": {}}}",
+        arg.position,
+        string(&arg.name),
+        string(&arg.comment),
+        strings(Modifier::names(arg.modifiers))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tiny::parse_tiny;
+
+    #[test]
+    fn escaped_strings() {
+        assert_eq!(string("a\"b\\c"), r#""a\"b\\c""#);
+        assert_eq!(string("line\nnext\ttab\u{1}"), r#""line\nnext\ttab\u0001""#);
+        assert_eq!(optional_string(None), "null");
+    }
+
+    #[test]
+    fn mappings() {
+        let mut mappings = Mappings::new();
+        parse_tiny(
+            "tiny\t2\t0\tintermediary\tnamed
+c\tnet/minecraft/class_1\tnet/minecraft/block/Block
+\tc\tA \"block\"
+\tm\t(I)V\tmethod_1\tonSteppedOn
+\t\tp\t1\t\tlight
+",
+            &mut mappings,
+        )
+        .unwrap();
+        mappings.complete_signatures();
+        assert_eq!(
+            write_json(&mappings),
+            r#"{"namespaces": ["official", "intermediary", "named", "mojang"], "classes": [
+  {"names": {"intermediary": "net/minecraft/class_1", "named": "net/minecraft/block/Block"}, "comment": "A \"block\"", "modifiers": [], "modifiers_known": false, "super_class": null, "interfaces": [], "signatures": {}, "fields": [], "methods": [
+    {"names": {"intermediary": "method_1", "named": "onSteppedOn"}, "comment": "", "modifiers": [], "descriptors": {"official": "(I)V", "intermediary": "(I)V", "named": "(I)V", "mojang": "(I)V"}, "signatures": {}, "args": [{"position": 1, "name": "light", "comment": "", "modifiers": []}]}
+  ]}
+]}"#
+        );
+    }
+}
@@ -1,28 +1,85 @@
 use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, quote};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     env,
     fmt::Write,
-    fs::{self, DirEntry, File},
-    io::{self, Read},
+    fs::{self, DirEntry},
+    io,
     iter::Peekable,
     ops::BitAnd,
-    path::Path,
+    path::{Path, PathBuf},
     str::{Chars, Lines},
     sync::LazyLock,
 };
+pub mod cache;
+pub mod classfile;
 pub mod codegen;
+pub mod diff;
+pub mod enigma;
+pub mod error;
+pub mod generics;
+pub mod index;
+pub mod jar;
+pub mod json;
+pub mod migrate;
+pub mod mojang;
+pub mod namespace;
+pub mod tiny;
+pub mod types;
+
+use cache::CacheKey;
+use classfile::ClassFile;
+use codegen::{doc_attributes, javadoc_to_markdown};
+pub use error::MappingsError;
+use generics::{ClassSignature, GenericSignature, GenericType, MethodSignature, TypeArgument};
+use index::MappingsIndex;
+pub use namespace::{Names, Namespace, TARGET};
+use types::TYPE_MAPPINGS;
 
 use bitcode::{Decode, Encode};
 
 pub static MAPPINGS: LazyLock<Mappings> = LazyLock::new(|| parse_mappings().into());
+/// Index written next to the cache by `prepare_mappings`, so proc macros don't decode every class
+static INDEX: LazyLock<MappingsIndex> = LazyLock::new(|| {
+    let key = CacheKey::recorded().expect(
+        "No mappings were prepared, they are cached by the build script of rustcraft_codegen",
+    );
+    let path = key.index_path();
+    MappingsIndex::read(&path).unwrap_or_else(|| {
+        panic!(
+            "Missing or outdated mappings index {:?}, rebuild rustcraft_codegen to write it",
+            path
+        )
+    })
+});
 
 #[derive(Encode, Decode, Debug, Clone)]
 pub enum Modifier {
     None = 0,
     Static = 1,
     Nullable = 2,
+    Final = 4,
+    /// Enum classes and their constants
+    Enum = 8,
+    Interface = 16,
+}
+
+impl Modifier {
+    /// Lowercase names of the modifiers set in `modifiers`, like `["static", "final"]`
+    pub fn names(modifiers: u8) -> Vec<&'static str> {
+        [
+            (Modifier::Static, "static"),
+            (Modifier::Nullable, "nullable"),
+            (Modifier::Final, "final"),
+            (Modifier::Enum, "enum"),
+            (Modifier::Interface, "interface"),
+        ]
+        .into_iter()
+        .filter(|(modifier, _)| modifier.clone() & modifiers)
+        .map(|(_, name)| name)
+        .collect()
+    }
 }
 
 impl BitAnd<u8> for Modifier {
@@ -43,30 +100,45 @@ impl BitAnd<Modifier> for u8 {
 
 #[derive(Encode, Decode, Debug, Clone)]
 pub struct Class {
-    pub intermediary_name: String,
-    pub mapped_name: String,
+    pub names: Names,
+    /// Intermediary name of the super class, read from the game jar
+    pub super_class: Option<String>,
+    /// Intermediary names of the implemented interfaces, read from the game jar
+    pub interfaces: Vec<String>,
+    /// Generic signature in each namespace, read from the game jar. Empty if the class has no
+    /// type parameters, see `generics::ClassSignature`
+    pub signatures: Names,
     pub comments: String,
+    pub modifiers: u8,
+    /// Whether the modifiers of the class and its members were read from the game jar. They are all
+    /// `Modifier::None` otherwise, as `rustcraft_mappings_gen` only reads those of the members
+    pub modifiers_known: bool,
     pub fields: HashMap<String, Field>,
-    pub methods_nosig: HashMap<String, String>,
+    /// Method names to the keys of every overload in `methods`
+    pub methods_nosig: HashMap<String, Vec<String>>,
     pub methods: HashMap<String, Method>,
 }
 
 #[derive(Encode, Decode, Debug, Clone)]
 pub struct Field {
-    pub intermediary_name: String,
-    pub mapped_name: String,
-    pub field_type: String,
-    pub mapped_field_type: String,
+    pub names: Names,
+    /// Field type in each namespace, only the intermediary one is set by parsers
+    pub descriptors: Names,
+    /// Generic signature in each namespace, read from the game jar. Empty if the type doesn't
+    /// involve type parameters
+    pub signatures: Names,
     pub comments: String,
     pub modifiers: u8,
 }
 
 #[derive(Encode, Decode, Debug, Clone)]
 pub struct Method {
-    pub intermediary_name: String,
-    pub mapped_name: String,
-    pub signature: String,
-    pub mapped_signature: String,
+    pub names: Names,
+    /// Signature in each namespace, only the intermediary one is set by parsers
+    pub descriptors: Names,
+    /// Generic signature in each namespace, read from the game jar. Empty if the method doesn't
+    /// involve type parameters
+    pub signatures: Names,
     pub comments: String,
     pub args: Vec<Arg>,
     pub modifiers: u8,
@@ -79,95 +151,779 @@ pub struct Arg {
     pub comment: String,
     pub modifiers: u8,
 }
-pub fn is_dev() -> bool {
-    std::env::var("DEV_MAPPINGS").is_ok_and(|x| x == "1")
+
+macro_rules! impl_names {
+    ($($t:ty),*) => {$(
+        impl $t {
+            pub fn name(&self, ns: Namespace) -> &str {
+                self.names.name(ns)
+            }
+            pub fn intermediary_name(&self) -> &str {
+                self.name(Namespace::INTERMEDIARY)
+            }
+            pub fn mapped_name(&self) -> &str {
+                self.name(Namespace::NAMED)
+            }
+            /// Name in the `TARGET` namespace
+            pub fn get_java_name(&self) -> &str {
+                self.name(TARGET)
+            }
+        }
+    )*};
 }
+impl_names!(Class, Field, Method);
 
 impl Field {
-    pub fn get_java_name(&self) -> &str {
-        if is_dev() {
-            &self.mapped_name
-        } else {
-            &self.intermediary_name
-        }
+    pub fn descriptor(&self, ns: Namespace) -> &str {
+        self.descriptors.name(ns)
     }
     pub fn get_java_type(&self) -> &str {
-        if is_dev() {
-            &self.mapped_field_type
+        self.descriptor(TARGET)
+    }
+
+    /// Generic type in the named namespace, type variables which aren't type parameters of the
+    /// class being erased
+    pub fn generic_type(&self, class_type_parameters: &[String]) -> GenericType {
+        let erased =
+            GenericType::parse(self.descriptor(Namespace::NAMED)).expect("Invalid descriptor");
+        let kept = if self.modifiers & Modifier::Static {
+            &[]
         } else {
-            &self.field_type
+            class_type_parameters
+        };
+        match self
+            .signatures
+            .get(Namespace::NAMED)
+            .and_then(GenericType::parse)
+        {
+            Some(GenericType::Variable(name)) if !kept.contains(&name) => erased,
+            Some(mut generic_type) => {
+                generic_type.erase_variables(kept);
+                generic_type
+            }
+            None => erased,
         }
     }
 }
 
 impl Method {
-    pub fn get_java_name(&self) -> &str {
-        if is_dev() {
-            &self.mapped_name
-        } else {
-            &self.intermediary_name
+    /// Index in the descriptor of the parameter stored in the local variable `slot`
+    pub fn parameter_index(&self, slot: u16) -> Option<usize> {
+        // `this` takes the first slot of instance methods, yarn numbers arguments of static
+        // methods from 0
+        let mut current =
+            if self.modifiers & Modifier::Static || self.args.iter().any(|arg| arg.position == 0) {
+                0
+            } else {
+                1
+            };
+        for (i, arg) in parse_java_signature(self.descriptor(Namespace::INTERMEDIARY))?
+            .args
+            .iter()
+            .enumerate()
+        {
+            if current == slot {
+                return Some(i);
+            }
+            // Longs and doubles take two slots
+            current += match arg {
+                SigType::Long | SigType::Double => 2,
+                _ => 1,
+            };
         }
+        None
+    }
+
+    /// Mapped argument of the parameter at `index` in the descriptor
+    pub fn arg(&self, index: usize) -> Option<&Arg> {
+        self.args
+            .iter()
+            .find(|arg| self.parameter_index(arg.position) == Some(index))
+    }
+
+    /// Rust names of the first `count` parameters, from the mapped argument names when known
+    pub fn arg_rust_names(&self, count: usize) -> Vec<String> {
+        let mut names: Vec<String> = vec![];
+        for i in 0..count {
+            let mut name = self
+                .arg(i)
+                .filter(|arg| !arg.name.is_empty())
+                .map(|arg| java_to_rust_field(&arg.name))
+                .unwrap_or_default();
+            if !name.is_empty() {
+                normalize(&mut name, "arg_");
+            }
+            // Locals of generated methods can't be shadowed
+            if name.is_empty()
+                || ["api", "value", "class"].contains(&name.as_str())
+                || names.contains(&name)
+            {
+                name = format!("arg{}", i);
+            }
+            names.push(name);
+        }
+        names
+    }
+
+    pub fn is_constructor(&self) -> bool {
+        self.mapped_name() == "<init>"
+    }
+    pub fn descriptor(&self, ns: Namespace) -> &str {
+        self.descriptors.name(ns)
     }
     pub fn get_java_sig(&self) -> &str {
-        if is_dev() {
-            &self.mapped_signature
+        self.descriptor(TARGET)
+    }
+
+    /// Generic signature in the named namespace, type variables which aren't type parameters of
+    /// the class being erased, to the descriptor type when they are a whole argument or the
+    /// return type. The descriptor is used when the signature doesn't describe every argument,
+    /// like synthetic arguments of constructors of inner classes
+    pub fn generic_signature(&self, class_type_parameters: &[String]) -> MethodSignature {
+        let erased =
+            MethodSignature::parse(self.descriptor(Namespace::NAMED)).expect("Invalid descriptor");
+        let Some(mut signature) = self
+            .signatures
+            .get(Namespace::NAMED)
+            .and_then(MethodSignature::parse)
+            .filter(|signature| signature.args.len() == erased.args.len())
+        else {
+            return erased;
+        };
+        // Type parameters of the method shadow the ones of the class
+        let kept: Vec<String> = if self.modifiers & Modifier::Static {
+            vec![]
         } else {
-            &self.signature
+            class_type_parameters
+                .iter()
+                .filter(|name| !signature.type_parameters.iter().any(|p| &&p.name == name))
+                .cloned()
+                .collect()
+        };
+        for (generic_type, erased) in signature
+            .args
+            .iter_mut()
+            .chain(std::iter::once(&mut signature.ret))
+            .zip(erased.args.into_iter().chain(std::iter::once(erased.ret)))
+        {
+            match generic_type {
+                GenericType::Variable(name) if !kept.contains(name) => *generic_type = erased,
+                generic_type => generic_type.erase_variables(&kept),
+            }
         }
+        signature
     }
 }
 
 impl Class {
-    pub fn get_java_name(&self) -> &str {
-        if is_dev() {
-            &self.mapped_name
+    /// Whether the class is a java enum, from the access flags of the class or of its constants.
+    /// Otherwise enums are subclasses of `java/lang/Enum` with a static array of their own type,
+    /// the synthetic `$VALUES` field, whatever name the mappings give it
+    pub fn is_enum(&self) -> bool {
+        if self.modifiers & Modifier::Enum
+            || self
+                .fields
+                .values()
+                .any(|field| field.modifiers & Modifier::Enum)
+        {
+            return true;
+        }
+        let values_type = format!("[L{};", self.intermediary_name());
+        self.super_class.as_deref() == Some("java/lang/Enum")
+            && self.fields.values().any(|field| {
+                field.descriptor(Namespace::INTERMEDIARY) == values_type
+                    && (field.modifiers & Modifier::Static || !self.modifiers_known)
+            })
+    }
+
+    /// Whether a `{Name}Interface` trait is generated for the class, implemented by the wrappers
+    /// of its subclasses. Interfaces and non-final classes have one, classes are assumed to be
+    /// non-final when the game jar is unknown
+    pub fn has_interface_trait(&self) -> bool {
+        !self.is_enum()
+            && (self.modifiers & Modifier::Interface || !(self.modifiers & Modifier::Final))
+    }
+
+    /// Constants of an enum class with their keys in `fields`, in the order of their
+    /// intermediary names. Without access flags, constants are the upper case fields of the
+    /// type of the class
+    pub fn enum_constants(&self) -> Vec<(&str, &Field)> {
+        let own_type = format!("L{};", self.intermediary_name());
+        let flagged = self
+            .fields
+            .values()
+            .any(|field| field.modifiers & Modifier::Enum);
+        let mut constants: Vec<(&str, &Field)> = self
+            .fields
+            .iter()
+            .filter(|(_, field)| match flagged {
+                true => field.modifiers & Modifier::Enum,
+                false => {
+                    field.descriptor(Namespace::INTERMEDIARY) == own_type
+                        && !field.mapped_name().contains(|c: char| c.is_lowercase())
+                }
+            })
+            .map(|(key, field)| (key.as_str(), field))
+            .collect();
+        // `field_9` comes before `field_10`
+        constants.sort_by_key(|(_, field)| {
+            (
+                field.intermediary_name().len(),
+                field.intermediary_name().to_string(),
+            )
+        });
+        constants
+    }
+
+    /// Names of the type parameters of the class, read from its generic signature
+    pub fn type_parameters(&self) -> Vec<String> {
+        self.signatures
+            .get(Namespace::NAMED)
+            .and_then(ClassSignature::parse)
+            .map(|signature| {
+                signature
+                    .type_parameters
+                    .into_iter()
+                    .map(|parameter| parameter.name)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Path of the type generated for this class by `codegen`, from the root of the crate
+    /// including the bindings
+    pub fn rust_path(&self) -> String {
+        rust_path(self.mapped_name())
+    }
+
+    /// Find a method declared by this class only, `None` if `method` matches several overloads.
+    /// Inherited methods are found by `ClassLookup::find_method`
+    pub fn get_declared_method(&self, method: &str) -> Option<&Method> {
+        match self.select_methods(method).as_slice() {
+            [method] => Some(method),
+            _ => None,
+        }
+    }
+
+    /// Declared methods matching `selector`, which is either:
+    /// - a `name#descriptor` key
+    /// - a named name, matching every overload. Like classes, methods are also found by their name
+    ///   in the other namespaces
+    /// - a name followed by a partial signature, as a descriptor prefix like `setBlockState(Lnet/minecraft/util/math/BlockPos;`
+    ///   or as Java types like `setBlockState(BlockPos, BlockState, int)`. The parameters list is
+    ///   complete only when it is closed
+    pub fn select_methods(&self, selector: &str) -> Vec<&Method> {
+        if selector.contains('#') {
+            return self.methods.get(selector).into_iter().collect();
+        }
+        let (name, partial) = match selector.split_once('(') {
+            Some((name, partial)) => (name, Some(partial)),
+            None => (selector, None),
+        };
+        let named: Vec<&Method> = self
+            .methods_nosig
+            .get(name)
+            .into_iter()
+            .flatten()
+            .filter_map(|key| self.methods.get(key))
+            .collect();
+        let candidates = if named.is_empty() {
+            self.methods
+                .values()
+                .filter(|method| method.names.iter().any(|(_, n)| n == name))
+                .collect()
         } else {
-            &self.intermediary_name
+            named
+        };
+        let mut result: Vec<&Method> = candidates
+            .into_iter()
+            .filter(|method| {
+                partial.is_none_or(|partial| {
+                    method
+                        .descriptors
+                        .iter()
+                        .any(|(_, descriptor)| matches_partial_signature(descriptor, partial))
+                })
+            })
+            .collect();
+        result.sort_by_key(|method| method.descriptor(Namespace::NAMED));
+        result
+    }
+
+    /// Rust names of the generated methods, keyed like `methods`. Overloads get a suffix made of
+    /// their parameter types, like `set_block_state_block_pos_block_state_i32`, the overload without
+    /// parameters keeping the plain name. Constructors are always named this way after `new`, which
+    /// is taken by the wrapper constructor, `new_default` being the one without parameters
+    pub fn method_rust_names(&self) -> HashMap<&str, String> {
+        let mut result = HashMap::new();
+        for (name, keys) in self.methods_nosig.iter() {
+            let is_constructor = name == "<init>";
+            let rust_name = if is_constructor {
+                "new".to_string()
+            } else {
+                java_to_rust_method(name)
+            };
+            if let ([key], false) = (keys.as_slice(), is_constructor) {
+                result.insert(key.as_str(), rust_name);
+                continue;
+            }
+            let mut keys: Vec<&String> = keys.iter().collect();
+            keys.sort();
+            let mut used = HashSet::new();
+            for key in keys {
+                // Descriptors are checked when the mappings are read
+                let args = parse_java_signature(self.methods[key].descriptor(Namespace::NAMED))
+                    .map(|signature| signature.args)
+                    .unwrap_or_default();
+                let mut overload_name = rust_name.clone();
+                if is_constructor && args.is_empty() {
+                    overload_name += "_default";
+                }
+                for arg in args.iter() {
+                    overload_name.push('_');
+                    overload_name += &arg.overload_name();
+                }
+                // Bridge methods only differ by their return type
+                let mut unique_name = overload_name.clone();
+                let mut i = 2;
+                while !used.insert(unique_name.clone()) {
+                    unique_name = format!("{}_{}", overload_name, i);
+                    i += 1;
+                }
+                result.insert(key.as_str(), unique_name);
+            }
         }
+        result
     }
-    pub fn get_method(&self, method: &str) -> Option<&Method> {
-        self.methods.get(
-            self.methods_nosig
-                .get(method)
-                .unwrap_or(&method.to_string()),
-        )
+
+    /// Find a field declared by this class only, by named name, by its `RustConvention` key or by
+    /// its name in another namespace
+    pub fn get_declared_field(&self, field: &str) -> Option<&Field> {
+        self.fields
+            .get(field)
+            .or_else(|| self.fields.get(&to_rust_convention(field)))
+            .or_else(|| {
+                self.fields
+                    .values()
+                    .find(|f| f.names.iter().any(|(_, name)| name == field))
+            })
+    }
+
+    /// Fields sorted by intermediary name, so written mappings are stable
+    pub(crate) fn sorted_fields(&self) -> Vec<&Field> {
+        let mut fields: Vec<&Field> = self.fields.values().collect();
+        fields.sort_by_key(|field| field.intermediary_name());
+        fields
+    }
+
+    /// Methods sorted by intermediary name and descriptor
+    pub(crate) fn sorted_methods(&self) -> Vec<&Method> {
+        let mut methods: Vec<&Method> = self.methods.values().collect();
+        methods.sort_by_key(|method| {
+            (
+                method.intermediary_name(),
+                method.descriptor(Namespace::INTERMEDIARY),
+            )
+        });
+        methods
+    }
+
+    pub(crate) fn insert_field(&mut self, field: Field) {
+        self.fields
+            .insert(to_rust_convention(field.mapped_name()), field);
+    }
+
+    /// Methods are keyed with their intermediary signature, `Mappings::complete_signatures` remaps them
+    pub(crate) fn insert_method(&mut self, method: Method) {
+        let mapped_name = method.mapped_name().to_string();
+        let class_sig = format!(
+            "{}#{}",
+            mapped_name,
+            method.descriptor(Namespace::INTERMEDIARY)
+        );
+        self.methods_nosig
+            .entry(mapped_name)
+            .or_default()
+            .push(class_sig.clone());
+        self.methods.insert(class_sig, method);
     }
 }
 
 #[derive(Encode, Decode, Debug)]
 pub struct Mappings {
+    /// Names of the namespaces, indexed by `Namespace`
+    pub namespaces: Vec<String>,
+    /// Classes keyed by their named name
     pub mapped_map: HashMap<String, Class>,
-    pub intermediary_map: HashMap<String, String>,
+    /// For each namespace, class names to their key in `mapped_map`
+    pub class_names: Vec<HashMap<String, String>>,
 }
 
 impl Mappings {
     pub fn new() -> Self {
         Self {
+            namespaces: Namespace::DEFAULTS
+                .iter()
+                .map(|ns| ns.to_string())
+                .collect(),
             mapped_map: HashMap::new(),
-            intermediary_map: HashMap::new(),
+            class_names: vec![HashMap::new(); Namespace::DEFAULTS.len()],
+        }
+    }
+
+    pub fn namespace(&self, name: &str) -> Option<Namespace> {
+        self.namespaces
+            .iter()
+            .position(|ns| ns == name)
+            .map(|i| Namespace(i as u8))
+    }
+
+    /// Get a namespace by name, creating it if it doesn't exist yet
+    pub fn add_namespace(&mut self, name: &str) -> Namespace {
+        self.namespace(name).unwrap_or_else(|| {
+            self.namespaces.push(name.to_string());
+            self.class_names.push(HashMap::new());
+            Namespace((self.namespaces.len() - 1) as u8)
+        })
+    }
+
+    pub fn insert_class(&mut self, class: Class) {
+        let mapped_name = class.mapped_name().to_string();
+        for (ns, name) in class.names.iter() {
+            self.class_names[ns.index()].insert(name.to_string(), mapped_name.clone());
         }
+        self.mapped_map.insert(mapped_name, class);
+    }
+
+    /// Classes sorted by intermediary name
+    pub(crate) fn sorted_classes(&self) -> Vec<&Class> {
+        let mut classes: Vec<&Class> = self.mapped_map.values().collect();
+        classes.sort_by_key(|class| class.intermediary_name());
+        classes
     }
 
-    pub fn insert(&mut self, mapped_name: String, intermediary_name: String) {
-        self.intermediary_map.insert(intermediary_name, mapped_name);
+    fn index_classes(&mut self) {
+        for names in self.class_names.iter_mut() {
+            names.clear();
+        }
+        for (mapped_name, class) in self.mapped_map.iter() {
+            for (ns, name) in class.names.iter() {
+                self.class_names[ns.index()].insert(name.to_string(), mapped_name.clone());
+            }
+        }
     }
 
-    pub fn insert_class(&mut self, mapped_name: String, intermediary_name: String, class: Class) {
-        self.mapped_map.insert(mapped_name.clone(), class);
-        self.insert(mapped_name, intermediary_name);
+    /// Intermediary class names to their name in `ns`
+    fn intermediary_to(&self, ns: Namespace) -> HashMap<String, String> {
+        self.mapped_map
+            .values()
+            .map(|class| {
+                (
+                    class.intermediary_name().to_string(),
+                    class.name(ns).to_string(),
+                )
+            })
+            .collect()
+    }
+
+    /// Fill descriptors of every namespace once every class name is known
+    pub fn complete_signatures(&mut self) {
+        self.index_classes();
+        let namespaces: Vec<(Namespace, HashMap<String, String>)> = (0..self.namespaces.len())
+            .map(|i| Namespace(i as u8))
+            .filter(|ns| *ns != Namespace::INTERMEDIARY)
+            .map(|ns| (ns, self.intermediary_to(ns)))
+            .collect();
+        let named = self.intermediary_to(Namespace::NAMED);
+        let complete = |descriptors: &mut Names| {
+            let descriptor = descriptors.name(Namespace::INTERMEDIARY).to_string();
+            for (ns, classes) in namespaces.iter() {
+                descriptors.set(*ns, replace_mappings(classes, &descriptor));
+            }
+        };
+
+        for value in self.mapped_map.values_mut() {
+            for field in value.fields.values_mut() {
+                complete(&mut field.descriptors);
+            }
+
+            let mut new_methods = HashMap::new();
+            for mut method in std::mem::take(&mut value.methods).into_values() {
+                complete(&mut method.descriptors);
+                new_methods.insert(
+                    format!(
+                        "{}#{}",
+                        method.mapped_name(),
+                        method.descriptor(Namespace::NAMED)
+                    ),
+                    method,
+                );
+            }
+            value.methods = new_methods;
+            for keys in value.methods_nosig.values_mut() {
+                for key in keys.iter_mut() {
+                    let (name, sig) = key.split_once("#").unwrap();
+                    *key = format!("{}#{}", name, replace_mappings(&named, sig));
+                }
+            }
+        }
+    }
+
+    /// Find a class by its name in any namespace
+    pub fn get_opt(&self, name: &str) -> Option<&Class> {
+        self.mapped_map.get(name).or_else(|| {
+            self.class_names
+                .iter()
+                .find_map(|names| names.get(name))
+                .and_then(|mapped_name| self.mapped_map.get(mapped_name))
+        })
     }
 
     pub fn get(&self, name: &str) -> &Class {
-        match self.mapped_map.get(name) {
-            None => self
-                .mapped_map
-                .get(self.intermediary_map.get(name).expect(
-                    format!("Can't find class {} in intermediary and mapped name", name).as_str(),
+        self.class(name).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Namespace of the classes of a jar, the one matching the most class names, so both
+    /// obfuscated and remapped jars can be used
+    fn jar_namespace(&self, classes: &[ClassFile]) -> Namespace {
+        (0..self.namespaces.len())
+            .map(|i| Namespace(i as u8))
+            .max_by_key(|ns| {
+                classes
+                    .iter()
+                    .filter(|class| self.class_names[ns.index()].contains_key(&class.name))
+                    .count()
+            })
+            .unwrap()
+    }
+
+    /// Record super classes and interfaces of the classes of the game jar
+    pub fn complete_hierarchy(&mut self, classes: &[ClassFile]) {
+        let ns = self.jar_namespace(classes);
+        let to_intermediary = |name: &str| {
+            self.translate(name, ns, Namespace::INTERMEDIARY)
+                .unwrap_or(name)
+                .to_string()
+        };
+        let hierarchy: Vec<(String, Option<String>, Vec<String>)> = classes
+            .iter()
+            .filter_map(|class| {
+                let mapped_name = self.class_names[ns.index()].get(&class.name)?;
+                Some((
+                    mapped_name.clone(),
+                    class.super_class.as_deref().map(to_intermediary),
+                    class
+                        .interfaces
+                        .iter()
+                        .map(|i| to_intermediary(i))
+                        .collect(),
                 ))
-                .unwrap(),
-            Some(value) => value,
+            })
+            .collect();
+        for (mapped_name, super_class, interfaces) in hierarchy {
+            let class = self.mapped_map.get_mut(&mapped_name).unwrap();
+            class.super_class = super_class;
+            class.interfaces = interfaces;
         }
     }
+
+    /// Classes of the game jar found in the mappings, with their fields and methods
+    fn jar_classes<'c>(&self, classes: &'c [ClassFile], ns: Namespace) -> Vec<JarClass<'c>> {
+        classes
+            .iter()
+            .filter_map(|class_file| {
+                let mapped_name = self.class_names[ns.index()].get(&class_file.name)?;
+                let class = self.mapped_map.get(mapped_name)?;
+                let fields = class_file
+                    .fields
+                    .iter()
+                    .filter_map(|field_file| {
+                        let (key, _) = class.fields.iter().find(|(_, field)| {
+                            field.names.get(ns) == Some(&field_file.name)
+                                && field.descriptor(ns) == field_file.descriptor
+                        })?;
+                        Some((key.clone(), field_file))
+                    })
+                    .collect();
+                let methods: HashMap<(&str, &str), &String> = class
+                    .methods
+                    .iter()
+                    .filter_map(|(key, method)| {
+                        Some(((method.names.get(ns)?, method.descriptor(ns)), key))
+                    })
+                    .collect();
+                let methods = class_file
+                    .methods
+                    .iter()
+                    .filter_map(|method_file| {
+                        let key = methods
+                            .get(&(method_file.name.as_str(), method_file.descriptor.as_str()))?;
+                        Some((key.to_string(), method_file))
+                    })
+                    .collect();
+                Some(JarClass {
+                    mapped_name: mapped_name.clone(),
+                    file: class_file,
+                    fields,
+                    methods,
+                })
+            })
+            .collect()
+    }
+
+    /// Fill modifiers of members and arguments from the access flags and `Nullable` annotations
+    /// of the classes of the game jar
+    pub fn complete_modifiers(&mut self, classes: &[ClassFile]) {
+        let ns = self.jar_namespace(classes);
+        for jar_class in self.jar_classes(classes, ns) {
+            let class = self.mapped_map.get_mut(&jar_class.mapped_name).unwrap();
+            class.modifiers = Modifier::None as u8;
+            class.modifiers_known = true;
+            if jar_class.file.is_final() {
+                class.modifiers |= Modifier::Final as u8;
+            }
+            if jar_class.file.is_enum() {
+                class.modifiers |= Modifier::Enum as u8;
+            }
+            if jar_class.file.is_interface() {
+                class.modifiers |= Modifier::Interface as u8;
+            }
+            for (key, field_file) in jar_class.fields {
+                let field = class.fields.get_mut(&key).unwrap();
+                field.modifiers = Modifier::None as u8;
+                if field_file.is_static() {
+                    field.modifiers |= Modifier::Static as u8;
+                }
+                if field_file.is_nullable() {
+                    field.modifiers |= Modifier::Nullable as u8;
+                }
+                if field_file.is_final() {
+                    field.modifiers |= Modifier::Final as u8;
+                }
+                if field_file.is_enum() {
+                    field.modifiers |= Modifier::Enum as u8;
+                }
+            }
+            for (key, method_file) in jar_class.methods {
+                let method = class.methods.get_mut(&key).unwrap();
+                method.modifiers = Modifier::None as u8;
+                if method_file.is_static() {
+                    method.modifiers |= Modifier::Static as u8;
+                }
+                if method_file.is_nullable() {
+                    method.modifiers |= Modifier::Nullable as u8;
+                }
+                if method_file.is_final() {
+                    method.modifiers |= Modifier::Final as u8;
+                }
+                // Parameter indexes depend on the static modifier
+                let indexes: Vec<Option<usize>> = method
+                    .args
+                    .iter()
+                    .map(|arg| method.parameter_index(arg.position))
+                    .collect();
+                for (arg, index) in method.args.iter_mut().zip(indexes) {
+                    arg.modifiers = match index {
+                        Some(index) if method_file.is_parameter_nullable(index) => {
+                            Modifier::Nullable as u8
+                        }
+                        _ => Modifier::None as u8,
+                    };
+                }
+            }
+        }
+    }
+
+    /// Generic signature in every namespace, from a signature of the `from` namespace
+    fn signature_names<S: GenericSignature>(
+        &self,
+        signature: Option<&str>,
+        from: Namespace,
+    ) -> Names {
+        let mut names = Names::default();
+        let Some(signature) = signature else {
+            return names;
+        };
+        for i in 0..self.namespaces.len() {
+            let ns = Namespace(i as u8);
+            if let Some(signature) = self.translate_signature::<S>(signature, from, ns) {
+                names.set(ns, signature);
+            }
+        }
+        names
+    }
+
+    /// Record generic signatures of the classes of the game jar and of their members
+    pub fn complete_generics(&mut self, classes: &[ClassFile]) {
+        let ns = self.jar_namespace(classes);
+        let signatures: Vec<(JarClass, Names, Vec<Names>, Vec<Names>)> = self
+            .jar_classes(classes, ns)
+            .into_iter()
+            .map(|jar_class| {
+                let class =
+                    self.signature_names::<ClassSignature>(jar_class.file.signature.as_deref(), ns);
+                let fields = jar_class
+                    .fields
+                    .iter()
+                    .map(|(_, field)| {
+                        self.signature_names::<GenericType>(field.signature.as_deref(), ns)
+                    })
+                    .collect();
+                let methods = jar_class
+                    .methods
+                    .iter()
+                    .map(|(_, method)| {
+                        self.signature_names::<MethodSignature>(method.signature.as_deref(), ns)
+                    })
+                    .collect();
+                (jar_class, class, fields, methods)
+            })
+            .collect();
+        for (jar_class, class_signatures, fields, methods) in signatures {
+            let class = self.mapped_map.get_mut(&jar_class.mapped_name).unwrap();
+            class.signatures = class_signatures;
+            for ((key, _), signatures) in jar_class.fields.iter().zip(fields) {
+                class.fields.get_mut(key).unwrap().signatures = signatures;
+            }
+            for ((key, _), signatures) in jar_class.methods.iter().zip(methods) {
+                class.methods.get_mut(key).unwrap().signatures = signatures;
+            }
+        }
+    }
+
+    /// Translate a class name from a namespace to another
+    pub fn translate(&self, name: &str, from: Namespace, to: Namespace) -> Option<&str> {
+        self.class_names
+            .get(from.index())?
+            .get(name)
+            .and_then(|mapped_name| self.mapped_map.get(mapped_name))
+            .map(|class| class.name(to))
+    }
+
+    /// Translate every class of a field or method descriptor, unknown classes are kept as is
+    pub fn translate_descriptor(&self, descriptor: &str, from: Namespace, to: Namespace) -> String {
+        map_descriptor_classes(descriptor, |class| self.translate(class, from, to))
+    }
+
+    /// Translate every class of a generic signature, `None` if it is malformed
+    pub fn translate_signature<S: GenericSignature>(
+        &self,
+        signature: &str,
+        from: Namespace,
+        to: Namespace,
+    ) -> Option<String> {
+        let mut signature = S::parse(signature)?;
+        signature.map_classes(&|class| self.translate(class, from, to));
+        Some(signature.to_string())
+    }
+}
+
+/// A class of the game jar, with the keys of its members in the matching `Class`
+struct JarClass<'c> {
+    mapped_name: String,
+    file: &'c ClassFile,
+    fields: Vec<(String, &'c classfile::Member)>,
+    methods: Vec<(String, &'c classfile::Member)>,
 }
 
 fn to_rust_convention(s: &str) -> String {
@@ -186,7 +942,10 @@ pub fn method_to_java_convention(s: &str) -> String {
 pub fn normalize(s: &mut String, insert: &str) -> bool {
     match s.as_str() {
         "type" | "match" | "move" | "use" | "self" | "in" | "where" | "macro" | "impl" | "box"
-        | "mod" | "ref" | "as" | "true" | "false" | "continue" => {
+        | "mod" | "ref" | "as" | "true" | "false" | "continue" | "fn" | "let" | "loop"
+        | "struct" | "enum" | "trait" | "mut" | "pub" | "unsafe" | "extern" | "dyn" | "async"
+        | "await" | "crate" | "super" | "override" | "yield" | "virtual" | "priv" | "typeof"
+        | "unsized" | "become" | "gen" | "abstract" | "final" | "do" | "try" => {
             s.insert_str(0, insert);
             return true;
         }
@@ -206,6 +965,14 @@ pub fn rust_to_java_method(s: &str) -> String {
     result
 }
 
+/// Path of the type generated by `codegen` for the class named `class` in the named namespace
+pub fn rust_path(class: &str) -> String {
+    SigType::Object(class.to_string())
+        .get_constructor()
+        .to_string()
+        .replace(' ', "")
+}
+
 pub fn java_to_rust_class(s: &str) -> String {
     let mut result = s.to_string();
     normalize(&mut result, "Class");
@@ -269,6 +1036,7 @@ pub fn java_to_rust_field(s: &str) -> String {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub enum SigType {
     Boolean,
     Byte,
@@ -284,6 +1052,62 @@ pub enum SigType {
     Object(String),
 }
 impl SigType {
+    /// Type of the elements of an array, possibly nested, or the type itself
+    pub fn element(&self) -> &SigType {
+        match self {
+            SigType::Array(sig_type) => sig_type.element(),
+            sig_type => sig_type,
+        }
+    }
+
+    /// Name of the type in a generated overload name
+    pub fn overload_name(&self) -> String {
+        match self {
+            SigType::Boolean => "bool".to_string(),
+            SigType::Byte => "i8".to_string(),
+            SigType::Char => "u16".to_string(),
+            SigType::Short => "i16".to_string(),
+            SigType::Int => "i32".to_string(),
+            SigType::Long => "i64".to_string(),
+            SigType::Float => "f32".to_string(),
+            SigType::Double => "f64".to_string(),
+            SigType::Void => "void".to_string(),
+            SigType::Array(sig_type) => format!("{}_array", sig_type.overload_name()),
+            SigType::Object(s) => java_to_rust_method(s.rsplit(['/', '$']).next().unwrap()),
+        }
+    }
+
+    /// Whether `name`, a type as written in Java source like `int`, `BlockPos[]`,
+    /// `AbstractBlock.Settings` or `net.minecraft.util.math.BlockPos`, designates this type
+    pub fn matches_java_name(&self, name: &str) -> bool {
+        if let Some(inner) = name.strip_suffix("[]") {
+            return matches!(self, SigType::Array(sig_type) if sig_type.matches_java_name(inner.trim_end()));
+        }
+        let primitive = match self {
+            SigType::Boolean => "boolean",
+            SigType::Byte => "byte",
+            SigType::Char => "char",
+            SigType::Short => "short",
+            SigType::Int => "int",
+            SigType::Long => "long",
+            SigType::Float => "float",
+            SigType::Double => "double",
+            SigType::Void => "void",
+            SigType::Array(_) => return false,
+            SigType::Object(s) => {
+                // Dots separate either packages or nested classes
+                return [name.replace('.', "/"), name.replace('.', "$")]
+                    .iter()
+                    .any(|name| {
+                        s == name
+                            || s.ends_with(&format!("/{}", name))
+                            || s.ends_with(&format!("${}", name))
+                    });
+            }
+        };
+        name == primitive
+    }
+
     pub fn jni_name(&self) -> char {
         match self {
             SigType::Boolean => 'z',
@@ -340,6 +1164,330 @@ impl SigType {
         .into()
     }
 }
+/// Class having a wrapper struct, enums being generated as Rust enums
+fn wrapped_class(name: &str) -> Option<&'static Class> {
+    MAPPINGS.get_opt(name).filter(|class| !class.is_enum())
+}
+
+/// Tokens passing a parameter of a generated method to java
+struct ArgTokens {
+    /// Rust type of the parameter
+    param: TokenStream,
+    /// Statements creating the java objects needed by `value`
+    conversion: TokenStream,
+    /// `JValue` given to JNI
+    value: TokenStream,
+    /// Predicates of the `where` clause of the method
+    bounds: Vec<TokenStream>,
+}
+
+/// Return type and result conversion of a generated binding, with the tokens of its parameters.
+/// `None` if one of the types has no Rust counterpart or can't be sent to java, see
+/// `types::TYPE_MAPPINGS`, so the binding is skipped
+fn binding_tokens(
+    ret: &GenericType,
+    nullable_return: bool,
+    args: &[(Ident, &GenericType, bool)],
+) -> Option<((TokenStream, TokenStream), Vec<ArgTokens>)> {
+    let ret = ret.return_tokens(nullable_return)?;
+    let args = args
+        .iter()
+        .map(|(name, arg, nullable)| arg.arg_tokens(name, *nullable))
+        .collect::<Option<_>>()?;
+    Some((ret, args))
+}
+
+impl SigType {
+    fn jvalue_variant(&self) -> Option<Ident> {
+        let variant = match self {
+            SigType::Boolean => "Bool",
+            SigType::Byte => "Byte",
+            SigType::Char => "Char",
+            SigType::Short => "Short",
+            SigType::Int => "Int",
+            SigType::Long => "Long",
+            SigType::Float => "Float",
+            SigType::Double => "Double",
+            _ => return None,
+        };
+        Some(format_ident!("{}", variant))
+    }
+}
+
+impl TypeArgument {
+    /// Rust type of the argument in returned values, wildcards being `AnyObject`
+    fn rust_type(&self) -> TokenStream {
+        match self {
+            TypeArgument::Extends(t) | TypeArgument::Exact(t) => t.rust_type(),
+            TypeArgument::Wildcard | TypeArgument::Super(_) => None,
+        }
+        .unwrap_or_else(|| quote!(crate::java::AnyObject<'a>))
+    }
+
+    /// Rust type of the argument in parameters, wildcards accepting any type
+    fn param_type(&self) -> TokenStream {
+        match self {
+            TypeArgument::Exact(t) => t.rust_type(),
+            _ => None,
+        }
+        .unwrap_or_else(|| quote!(impl Sized))
+    }
+}
+
+impl GenericType {
+    /// Path of the wrapper of a mapped class with its type arguments, `lifetime` being the one of
+    /// the wrapper. `None` for other types
+    fn mapped_class_type(&self, lifetime: TokenStream, param: bool) -> Option<TokenStream> {
+        let GenericType::Class { name, args } = self else {
+            return None;
+        };
+        let class = wrapped_class(name)?;
+        let path = SigType::Object(name.clone()).get_constructor();
+        // Raw types use the default type arguments
+        if args.is_empty() || args.len() != class.type_parameters().len() {
+            return Some(quote!(#path<#lifetime>));
+        }
+        let args = args.iter().map(|arg| match param {
+            true => arg.param_type(),
+            false => arg.rust_type(),
+        });
+        Some(quote!(#path<#lifetime, #(#args),*>))
+    }
+
+    /// Trait implemented by the wrappers of a non-generic class and of its subclasses, `None` for
+    /// other types
+    fn interface_trait(&self) -> Option<TokenStream> {
+        let GenericType::Class { name, .. } = self else {
+            return None;
+        };
+        let class = wrapped_class(name)?;
+        (class.has_interface_trait() && class.type_parameters().is_empty())
+            .then(|| SigType::Object(format!("{}Interface", name)).get_constructor())
+    }
+
+    /// Path of the Rust enum generated for a java enum, `None` for other types
+    fn enum_type(&self) -> Option<TokenStream> {
+        match self {
+            GenericType::Class { name, .. } if MAPPINGS.get_opt(name)?.is_enum() => {
+                Some(SigType::Object(name.clone()).get_constructor())
+            }
+            _ => None,
+        }
+    }
+
+    /// Rust type of returned values and fields, `None` if this type has no Rust counterpart.
+    /// Type variables are type parameters of the generated struct
+    fn rust_type(&self) -> Option<TokenStream> {
+        match self {
+            GenericType::Primitive(t) => Some(quote!(#t)),
+            GenericType::Variable(name) => {
+                let name = format_ident!("{}", name);
+                Some(quote!(#name))
+            }
+            GenericType::Array(t) => match t.as_ref() {
+                // Primitive arrays are copied, object ones are read lazily
+                GenericType::Primitive(t) => Some(quote!(Vec<#t>)),
+                t => {
+                    let t = t.rust_type()?;
+                    Some(quote!(crate::object::JavaArray<'a, #t>))
+                }
+            },
+            GenericType::Class { name, args } => self
+                .mapped_class_type(quote!('a), false)
+                .or_else(|| self.enum_type())
+                .or_else(|| {
+                    let args: Vec<TokenStream> = args.iter().map(|arg| arg.rust_type()).collect();
+                    Some(TYPE_MAPPINGS.get(name)?.rust_tokens(&args))
+                }),
+        }
+    }
+
+    /// Return type and expression converting the JNI `value` of a generated method or getter,
+    /// objects of `nullable` members being wrapped in an `Option`. `None` if this type has no
+    /// Rust counterpart
+    fn return_tokens(&self, nullable: bool) -> Option<(TokenStream, TokenStream)> {
+        let rust_type = self.rust_type()?;
+        let result = match self {
+            GenericType::Primitive(_) => return Some((rust_type, quote!(value))),
+            GenericType::Class { name, .. } if wrapped_class(name).is_some() => {
+                let result_constructor = SigType::Object(name.clone()).get_constructor();
+                quote! {
+                    #result_constructor::new(api.clone(), value)
+                }
+            }
+            _ => quote! {
+                crate::object::FromJava::from_java(api.clone(), value)
+            },
+        };
+        if nullable {
+            Some((
+                quote!(Option<#rust_type>),
+                quote! {
+                    if value.is_null() {
+                        None
+                    } else {
+                        Some(#result)
+                    }
+                },
+            ))
+        } else {
+            Some((rust_type, result))
+        }
+    }
+
+    /// Tokens of a parameter named `name`, `None` if this type can't be passed from Rust yet.
+    /// Objects of `nullable` parameters are taken as an `Option`
+    fn arg_tokens(&self, name: &Ident, nullable: bool) -> Option<ArgTokens> {
+        let tokens = match self {
+            GenericType::Primitive(SigType::Void) => return None,
+            GenericType::Primitive(SigType::Boolean) => ArgTokens {
+                param: quote!(bool),
+                conversion: quote!(),
+                value: quote!(jni::objects::JValue::Bool(#name as u8)),
+                bounds: vec![],
+            },
+            GenericType::Primitive(primitive) => {
+                let variant = primitive.jvalue_variant()?;
+                ArgTokens {
+                    param: quote!(#primitive),
+                    conversion: quote!(),
+                    value: quote!(jni::objects::JValue::#variant(#name)),
+                    bounds: vec![],
+                }
+            }
+            GenericType::Class { .. } if self.mapped_class_type(quote!('_), true).is_some() => {
+                // Wrappers of subclasses are accepted through the trait of the class, anonymous
+                // lifetimes not being allowed in `impl Trait`
+                let interface = self.interface_trait();
+                let inner = |object: TokenStream| match interface {
+                    Some(_) => quote!(crate::object::JavaObject::get_inner(#object)),
+                    None => quote!(&#object.inner),
+                };
+                let path = match &interface {
+                    Some(interface) => quote!(impl #interface<'a>),
+                    None => self.mapped_class_type(quote!('_), true)?,
+                };
+                if nullable {
+                    let null = format_ident!("{}_null", name);
+                    return Some(ArgTokens {
+                        param: quote!(Option<&#path>),
+                        conversion: quote! {
+                            let #null = jni::objects::JObject::null();
+                        },
+                        value: {
+                            let inner = inner(quote!(o));
+                            quote!(jni::objects::JValue::Object(#name.map_or(&#null, |o| #inner)))
+                        },
+                        bounds: vec![],
+                    });
+                }
+                ArgTokens {
+                    param: quote!(&#path),
+                    conversion: quote!(),
+                    value: {
+                        let inner = inner(quote!(#name));
+                        quote!(jni::objects::JValue::Object(#inner))
+                    },
+                    bounds: vec![],
+                }
+            }
+            GenericType::Class { name: class, .. } => ArgTokens {
+                // Enums are taken by value like table types
+                param: self
+                    .enum_type()
+                    .or_else(|| TYPE_MAPPINGS.get(class)?.param_tokens())?,
+                conversion: quote! {
+                    let #name = crate::object::ToJava::to_java(#name, api.clone());
+                },
+                value: quote!(jni::objects::JValue::Object(&#name)),
+                bounds: vec![],
+            },
+            GenericType::Variable(variable) => {
+                let variable = format_ident!("{}", variable);
+                ArgTokens {
+                    param: quote!(&#variable),
+                    conversion: quote! {
+                        let #name = crate::object::ToJava::to_java(#name, api.clone());
+                    },
+                    value: quote!(jni::objects::JValue::Object(&#name)),
+                    bounds: vec![quote!(for<'b> &'b #variable: crate::object::ToJava)],
+                }
+            }
+            GenericType::Array(t) => match t.as_ref() {
+                GenericType::Primitive(SigType::Void)
+                | GenericType::Array(_)
+                | GenericType::Variable(_) => return None,
+                GenericType::Class { name: class, .. } if wrapped_class(class).is_some() => {
+                    let class_name = MAPPINGS.get(class).get_java_name();
+                    let path = t.mapped_class_type(quote!('_), true)?;
+                    ArgTokens {
+                        param: quote!(&[&#path]),
+                        conversion: quote! {
+                            let #name = api.new_object_array(
+                                #class_name,
+                                &#name.iter().map(|o| &o.inner).collect::<Vec<_>>(),
+                            );
+                        },
+                        value: quote!(jni::objects::JValue::Object(&#name)),
+                        bounds: vec![],
+                    }
+                }
+                GenericType::Class { name: class, .. } => {
+                    let param = t
+                        .enum_type()
+                        .or_else(|| TYPE_MAPPINGS.get(class)?.param_tokens())?;
+                    let class = MAPPINGS
+                        .get_opt(class)
+                        .map_or(class.as_str(), |class| class.get_java_name());
+                    ArgTokens {
+                        param: quote!(&[#param]),
+                        conversion: quote! {
+                            let #name: Vec<_> = #name
+                                .iter()
+                                .map(|v| crate::object::ToJava::to_java(*v, api.clone()))
+                                .collect();
+                            let #name = api.new_object_array(#class, &#name.iter().collect::<Vec<_>>());
+                        },
+                        value: quote!(jni::objects::JValue::Object(&#name)),
+                        bounds: vec![],
+                    }
+                }
+                GenericType::Primitive(primitive) => ArgTokens {
+                    param: quote!(&[#primitive]),
+                    conversion: quote! {
+                        let #name = api.new_primitive_array(#name);
+                    },
+                    value: quote!(jni::objects::JValue::Object(&#name)),
+                    bounds: vec![],
+                },
+            },
+        };
+        if nullable && !matches!(self, GenericType::Primitive(_)) {
+            let ArgTokens {
+                param,
+                conversion,
+                value,
+                bounds,
+            } = tokens;
+            return Some(ArgTokens {
+                param: quote!(Option<#param>),
+                conversion: quote! {
+                    let #name = match #name {
+                        Some(#name) => {
+                            #conversion
+                            #name
+                        }
+                        None => jni::objects::JObject::null(),
+                    };
+                },
+                value,
+                bounds,
+            });
+        }
+        Some(tokens)
+    }
+}
+
 impl quote::ToTokens for SigType {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         tokens.extend::<TokenStream>(
@@ -356,9 +1504,21 @@ impl quote::ToTokens for SigType {
                 SigType::Array(sig_type) => {
                     let mut t = TokenStream::new();
                     sig_type.to_tokens(&mut t);
-                    quote! {Vec<#t>}
+                    match sig_type.as_ref() {
+                        // Primitive arrays are copied, object ones are read lazily
+                        SigType::Array(_) | SigType::Object(_) => {
+                            quote! {crate::object::JavaArray<'a, #t>}
+                        }
+                        _ => quote! {Vec<#t>},
+                    }
                 }
                 SigType::Object(s) => {
+                    if MAPPINGS.get_opt(s).is_some_and(Class::is_enum) {
+                        return tokens.extend(self.get_constructor());
+                    }
+                    if let (None, Some(mapping)) = (MAPPINGS.get_opt(s), TYPE_MAPPINGS.get(s)) {
+                        return tokens.extend(mapping.rust_tokens(&[]));
+                    }
                     let mut t = vec![];
                     let length = s.split('/').count();
                     for (i, pat) in s.split('/').enumerate() {
@@ -388,218 +1548,268 @@ impl quote::ToTokens for SigType {
 }
 
 impl Method {
-    pub fn to_tokens(&self, class_name: &str, _struct_name: &Ident) -> TokenStream {
+    /// Generate the binding of this method, `method_name` being its entry in `Class::method_rust_names`
+    pub fn to_tokens(
+        &self,
+        class_name: &str,
+        method_name: &str,
+        type_parameters: &[String],
+    ) -> TokenStream {
         let mut tokens = quote!();
-        let method_name = java_to_rust_method(&self.mapped_name);
 
-        let method_type = parse_java_signature(&self.mapped_signature);
+        let Some(method_type) = parse_java_signature(self.descriptor(Namespace::NAMED)) else {
+            return tokens;
+        };
         let jni_method_type = format_ident!("{}", method_type.ret.jni_name());
-        let method_ret = method_type.ret;
+        let generic_type = self.generic_signature(type_parameters);
         let method_sig = self.get_java_sig();
         let method_java_name = self.get_java_name();
 
-        // TODO avoid some skip, currently not supported
-        match &method_ret {
-            SigType::Object(o) => {
-                if o.contains("com/mojang")
-                    || o.contains("java/")
-                    || o.contains("org/")
-                    || o.contains("javax/")
-                    || o.contains("google/")
-                    || o.contains("it/")
-                    || o.contains("io/")
-                    || o.contains("jcraft/")
-                    || o.contains("ibm/")
-                    || o.contains("microsoft/")
-                    || o.contains("sun/")
-                {
-                    return quote!();
-                }
-            }
-            SigType::Array(_) => return quote!(),
-            _ => {}
+        if self.mapped_name().contains("lambda$") {
+            // Lambda inside function, skip
+            return quote!();
         }
 
-        // Wrap constructors
-        if self.mapped_name == "<init>" {
-            return quote!();
-        } else if self.mapped_name.contains("lambda$") {
-            // Lambda inside function, skip
+        let arg_names = self.arg_rust_names(generic_type.args.len());
+        let args: Vec<(Ident, &GenericType, bool)> = generic_type
+            .args
+            .iter()
+            .enumerate()
+            .map(|(i, arg)| {
+                let nullable = self
+                    .arg(i)
+                    .is_some_and(|arg| arg.modifiers & Modifier::Nullable);
+                (format_ident!("{}", arg_names[i]), arg, nullable)
+            })
+            .collect();
+        let Some(((return_type, result), arg_tokens)) = binding_tokens(
+            &generic_type.ret,
+            self.modifiers & Modifier::Nullable,
+            &args,
+        ) else {
             return quote!();
+        };
+
+        let mut params = vec![];
+        let mut conversions = quote!();
+        let mut values = vec![];
+        let mut bounds = vec![];
+        for ((name, _, _), arg) in args.iter().zip(arg_tokens) {
+            let param = arg.param;
+            params.push(quote!(#name: #param));
+            conversions.extend(arg.conversion);
+            values.push(arg.value);
+            bounds.extend(arg.bounds);
         }
+        let where_clause = if bounds.is_empty() {
+            quote!()
+        } else {
+            quote!(where #(#bounds),*)
+        };
+
+        let arg_docs: Vec<String> = arg_names
+            .iter()
+            .enumerate()
+            .filter_map(|(i, name)| {
+                let comment = javadoc_to_markdown(self.arg(i)?.comment.trim());
+                (!comment.is_empty())
+                    .then(|| format!("* `{}` - {}", name, comment.replace('\n', "\n  ")))
+            })
+            .collect();
+        let doc = if arg_docs.is_empty() {
+            doc_attributes(&self.comments, &[], &self.names)
+        } else {
+            let arguments = format!("# Arguments\n\n{}", arg_docs.join("\n"));
+            doc_attributes(&self.comments, &[arguments], &self.names)
+        };
 
         let method_ident = format_ident!("{}", method_name);
+        // Wrap constructors
+        if self.is_constructor() {
+            return quote! {
+                #doc
+                pub fn #method_ident(api: crate::api::ModApi<'a>, #(#params),*) -> Self #where_clause {
+                    static CONSTRUCTOR: crate::cache::CachedMethod =
+                        crate::cache::CachedMethod::new(#class_name, "<init>", #method_sig);
+                    #conversions
+                    let inner = api.new_cached_object(&CONSTRUCTOR, &[#(#values),*]);
+                    Self::new(api, inner)
+                }
+            };
+        }
         let mut method_content = quote!();
         let args;
         if self.modifiers & Modifier::Static {
-            args = quote! {api: &'a mut crate::api::ModApi<'a>};
-            method_content.extend::<TokenStream>(
-                quote! {
-                        let class = api.get_class(#class_name);
-                        let value =
-                             api
-                             .call_static_method(
-                                 &class,
-                                 (#method_java_name, #method_sig),
-                                 &[],
-                             )
+            args = quote! {api: &'a mut crate::api::ModApi<'a>, #(#params),*};
+            method_content.extend(quote! {
+                        static METHOD: crate::cache::CachedStaticMethod =
+                            crate::cache::CachedStaticMethod::new(#class_name, #method_java_name, #method_sig);
+                        #conversions
+                        let value = api
+                             .call_cached_static_method(&METHOD, &[#(#values),*])
                              .#jni_method_type()
                              .unwrap();
-                }
-                .into(),
-            );
+                });
         } else {
-            args = quote! {&'a self};
-            method_content.extend::<TokenStream>(
-                quote! {
+            args = quote! {&'a self, #(#params),*};
+            method_content.extend(quote! {
+                        static METHOD: crate::cache::CachedMethod =
+                            crate::cache::CachedMethod::new(#class_name, #method_java_name, #method_sig);
                         let api = &self.api;
+                        #conversions
                         let value = api
-                             .call_method_class(
-                                 Some(#class_name),
-                                 Some(&self.inner),
-                                 (#method_java_name, #method_sig),
-                                 &[],
-                             )
+                             .call_cached_method(&self.inner, &METHOD, &[#(#values),*])
                              .#jni_method_type()
                              .unwrap();
-                }
-                .into(),
-            );
-        }
-        match method_ret {
-            SigType::Object(_) => {
-                let result_constructor = method_ret.get_constructor();
-                method_content.extend(quote! {
-                    #result_constructor::new(api.clone(), value)
-                })
-            }
-            _ => method_content.extend(quote! {
-                value
-            }),
+                });
         }
-        tokens.extend::<TokenStream>(
-            quote! {
-                pub fn #method_ident(#args) -> #method_ret {
-                    #method_content
-                }
+        method_content.extend(result);
+        tokens.extend(quote! {
+            #doc
+            pub fn #method_ident(#args) -> #return_type #where_clause {
+                #method_content
             }
-            .into(),
-        );
+        });
         tokens
     }
 }
 impl Field {
-    pub fn to_tokens(&self, class_name: &str, class: &Class) -> TokenStream {
+    pub fn to_tokens(
+        &self,
+        class_name: &str,
+        class: &Class,
+        type_parameters: &[String],
+    ) -> TokenStream {
         let mut tokens = quote!();
         let field_name = if self
-            .mapped_name
+            .mapped_name()
             .chars()
             .next()
             .unwrap()
             .is_ascii_uppercase()
         {
-            let mut field_name = self.mapped_name.clone();
+            let mut field_name = self.mapped_name().to_string();
             if class.methods_nosig.contains_key(&field_name) || normalize(&mut field_name, "") {
                 field_name = format!("{}_FIELD", field_name);
             }
             field_name
         } else {
-            let mut field_name = java_to_rust_field(&self.mapped_name);
-            if class.methods_nosig.contains_key(&self.mapped_name) || normalize(&mut field_name, "")
+            let mut field_name = java_to_rust_field(self.mapped_name());
+            if class.methods_nosig.contains_key(self.mapped_name())
+                || normalize(&mut field_name, "")
             {
                 field_name = format!("{}_field", field_name);
             }
             field_name
         };
 
-        let field_type = parse_type(&mut self.mapped_field_type.chars().peekable());
+        let Some(field_type) =
+            parse_type(&mut self.descriptor(Namespace::NAMED).chars().peekable())
+        else {
+            return tokens;
+        };
         let field_sig = self.get_java_type();
         let jni_field_type = format_ident!("{}", field_type.jni_name());
         let field_java_name = self.get_java_name();
 
-        // TODO avoid some skip, currently not supported
-        match &field_type {
-            SigType::Object(o) => {
-                if o.contains("com/mojang")
-                    || o.contains("java/")
-                    || o.contains("org/")
-                    || o.contains("javax/")
-                    || o.contains("google/")
-                    || o.contains("it/")
-                    || o.contains("io/")
-                    || o.contains("jcraft/")
-                    || o.contains("ibm/")
-                    || o.contains("microsoft/")
-                    || o.contains("sun/")
-                    || o.contains("jdk/")
-                    || o.contains("oshi/")
-                {
-                    return quote!();
-                }
-            }
-            SigType::Array(_) => return quote!(),
-            _ => {}
-        }
+        let field_generic_type = self.generic_type(type_parameters);
+        let nullable = self.modifiers & Modifier::Nullable;
+        let Some(((return_type, result), _)) = binding_tokens(&field_generic_type, nullable, &[])
+        else {
+            return quote!();
+        };
         let field_ident = format_ident!("{}", field_name);
+        let doc = doc_attributes(&self.comments, &[], &self.names);
         let mut field_content = quote!();
         let args;
         if self.modifiers & Modifier::Static {
             args = quote! {api: &'a mut crate::api::ModApi<'a>};
-            field_content.extend::<TokenStream>(
-                quote! {
-                        let class = api.get_class(#class_name);
-                        let value =
-                             api
-                             .get_static_field(
-                                 &class,
-                                 (#field_java_name, #field_sig)
-                             )
+            field_content.extend(quote! {
+                        static FIELD: crate::cache::CachedStaticField =
+                            crate::cache::CachedStaticField::new(#class_name, #field_java_name, #field_sig);
+                        let value = api
+                             .get_cached_static_field(&FIELD)
                              .#jni_field_type()
                              .unwrap();
-                }
-                .into(),
-            );
+                });
         } else {
             args = quote! {&'a self};
-            field_content.extend::<TokenStream>(
-                quote! {
-                        let api = &self.api;
-                        let value = api
-                             .get_field_class(
-                                 Some(#class_name),
-                                 Some(&self.inner),
-                                 (#field_java_name, #field_sig)
-                             )
-                             .#jni_field_type()
-                             .unwrap();
-                }
-                .into(),
-            );
+            field_content.extend(quote! {
+                    static FIELD: crate::cache::CachedField =
+                        crate::cache::CachedField::new(#class_name, #field_java_name, #field_sig);
+                    let api = &self.api;
+                    let value = api
+                         .get_cached_field(&self.inner, &FIELD)
+                         .#jni_field_type()
+                         .unwrap();
+            });
         }
-        match field_type {
-            SigType::Object(_) => {
-                let result_constructor = field_type.get_constructor();
-                field_content.extend(quote! {
-                    #result_constructor::new(api.clone(), value)
-                })
+        field_content.extend(result);
+        tokens.extend(quote! {
+            #doc
+            pub fn #field_ident(#args) -> #return_type {
+                #field_content
             }
-            _ => field_content.extend(quote! {
-                value
-            }),
+        });
+
+        // Final fields can't be written, and any field may be final when modifiers are unknown
+        if self.modifiers & Modifier::Final || !class.modifiers_known {
+            return tokens;
         }
-        tokens.extend::<TokenStream>(
-            quote! {
-                pub fn #field_ident(#args) -> #field_type {
-                    #field_content
+        let void = GenericType::Primitive(SigType::Void);
+        let value = (format_ident!("value"), &field_generic_type, nullable);
+        let Some((_, mut args)) = binding_tokens(&void, false, &[value]) else {
+            return tokens;
+        };
+        let ArgTokens {
+            param,
+            conversion,
+            value,
+            bounds,
+        } = args.remove(0);
+        let mut setter_name = format!("set_{}", field_name);
+        if class
+            .method_rust_names()
+            .values()
+            .any(|name| *name == setter_name)
+        {
+            setter_name = format!("{}_field", setter_name);
+        }
+        let setter_ident = format_ident!("{}", setter_name);
+        let setter_doc = format!(" Set the value of [`Self::{}`]", field_name);
+        let where_clause = if bounds.is_empty() {
+            quote!()
+        } else {
+            quote!(where #(#bounds),*)
+        };
+        if self.modifiers & Modifier::Static {
+            tokens.extend(quote! {
+                #[doc = #setter_doc]
+                pub fn #setter_ident(api: &'a mut crate::api::ModApi<'a>, value: #param) #where_clause {
+                    static FIELD: crate::cache::CachedStaticField =
+                        crate::cache::CachedStaticField::new(#class_name, #field_java_name, #field_sig);
+                    #conversion
+                    api.set_cached_static_field(&FIELD, #value);
                 }
-            }
-            .into(),
-        );
+            });
+        } else {
+            tokens.extend(quote! {
+                #[doc = #setter_doc]
+                pub fn #setter_ident(&self, value: #param) #where_clause {
+                    static FIELD: crate::cache::CachedField =
+                        crate::cache::CachedField::new(#class_name, #field_java_name, #field_sig);
+                    let api = &self.api;
+                    #conversion
+                    api.set_cached_field(&self.inner, &FIELD, #value);
+                }
+            });
+        }
         tokens
     }
 }
-pub fn parse_type(letters: &mut Peekable<Chars>) -> SigType {
-    match letters.next().unwrap() {
+/// Parse one type of a descriptor, `None` if it isn't valid
+pub fn parse_type(letters: &mut Peekable<Chars>) -> Option<SigType> {
+    let sig_type = match letters.next()? {
         'Z' => SigType::Boolean,
         'B' => SigType::Byte,
         'C' => SigType::Char,
@@ -609,37 +1819,72 @@ pub fn parse_type(letters: &mut Peekable<Chars>) -> SigType {
         'F' => SigType::Float,
         'D' => SigType::Double,
         'V' => SigType::Void,
-        '[' => SigType::Array(Box::new(parse_type(letters))),
+        '[' => SigType::Array(Box::new(parse_type(letters)?)),
         'L' => {
             let mut current_obj = String::new();
-            while let Some(c) = letters.next() {
-                if c == ';' {
-                    break;
+            loop {
+                match letters.next()? {
+                    ';' => break,
+                    c => current_obj.push(c),
                 }
-                current_obj.push(c);
             }
             SigType::Object(current_obj)
         }
-        c => unreachable!("{:?}: {}", letters, c),
+        _ => return None,
+    };
+    Some(sig_type)
+}
+
+/// Whether `descriptor` matches `partial`, the part of a method selector following the opening
+/// parenthesis. See `Class::select_methods`
+fn matches_partial_signature(descriptor: &str, partial: &str) -> bool {
+    let is_descriptor = partial.contains(';') || partial.chars().all(|c| "ZBCSIJFDV[)".contains(c));
+    if is_descriptor {
+        return descriptor
+            .strip_prefix('(')
+            .is_some_and(|descriptor| descriptor.starts_with(partial));
     }
+    let (args, closed) = match partial.split_once(')') {
+        Some((args, _)) => (args, true),
+        None => (partial, false),
+    };
+    let args: Vec<&str> = args
+        .split(',')
+        .map(|arg| arg.trim())
+        .filter(|arg| !arg.is_empty())
+        .collect();
+    let Some(signature) = parse_java_signature(descriptor) else {
+        return false;
+    };
+    if args.len() > signature.args.len() || (closed && args.len() != signature.args.len()) {
+        return false;
+    }
+    args.iter()
+        .zip(signature.args.iter())
+        .all(|(arg, sig_type)| sig_type.matches_java_name(arg))
 }
 
-pub fn parse_java_signature(s: &str) -> Signature {
+/// Parse a method descriptor, `None` if it isn't valid
+pub fn parse_java_signature(s: &str) -> Option<Signature> {
     let mut letters = s.chars().peekable();
-    letters.next();
+    if letters.next()? != '(' {
+        return None;
+    }
     let mut args = vec![];
-    while Some(')') != letters.peek().copied() {
-        args.push(parse_type(&mut letters));
+    while letters.next_if_eq(&')').is_none() {
+        args.push(parse_type(&mut letters)?);
     }
-    letters.next();
-    let ret = parse_type(&mut letters);
-    Signature { ret, args }
+    let ret = parse_type(&mut letters)?;
+    Some(Signature { ret, args })
 }
 
 fn parse_comments(lines: &mut Peekable<Lines>) -> String {
     let mut result = String::new();
     while let Some(line) = lines.peek() {
         if line.contains("COMMENT") {
+            if !result.is_empty() {
+                result.push('\n');
+            }
             result += line.replace("COMMENT", "").trim();
             lines.next();
         } else {
@@ -649,15 +1894,19 @@ fn parse_comments(lines: &mut Peekable<Lines>) -> String {
     result
 }
 
-fn parse_method_args(lines: &mut Peekable<Lines>) -> Vec<Arg> {
+fn parse_method_args(lines: &mut Peekable<Lines>) -> Result<Vec<Arg>, MappingsError> {
     let mut result = vec![];
     while let Some(line) = lines.peek() {
         if line.contains("ARG") {
-            let line = line.replace("ARG", "");
-            let (pos, name) = line.trim().split_once(" ").unwrap();
+            let arg = line.replace("ARG", "");
+            let (position, name) = arg
+                .trim()
+                .split_once(' ')
+                .and_then(|(position, name)| Some((position.parse::<u16>().ok()?, name)))
+                .ok_or_else(|| MappingsError::bad_line(line, "expected an index and a name"))?;
             lines.next();
             result.push(Arg {
-                position: pos.parse::<u16>().unwrap(),
+                position,
                 name: name.to_string(),
                 comment: parse_comments(lines),
                 modifiers: 0,
@@ -666,7 +1915,41 @@ fn parse_method_args(lines: &mut Peekable<Lines>) -> Vec<Arg> {
             break;
         }
     }
-    result
+    Ok(result)
+}
+
+/// Enigma files of yarn map intermediary names to named ones
+fn enigma_names(intermediary_name: String, mapped_name: String) -> Names {
+    let mut names = Names::default();
+    names.set(Namespace::INTERMEDIARY, intermediary_name);
+    names.set(Namespace::NAMED, mapped_name);
+    names
+}
+
+fn enigma_descriptor(descriptor: String) -> Names {
+    let mut descriptors = Names::default();
+    descriptors.set(Namespace::INTERMEDIARY, descriptor);
+    descriptors
+}
+
+/// Intermediary and mapped names of an Enigma entry, followed by the rest of the line
+fn parse_entry<'a>(
+    line: &'a str,
+    entry: &'a str,
+) -> Result<(&'a str, &'a str, &'a str), MappingsError> {
+    let mut columns = entry.split(' ');
+    let (intermediary_name, mapped_name, descriptor) =
+        match (columns.next(), columns.next(), columns.next()) {
+            (Some(intermediary), Some(mapped), Some(descriptor)) => {
+                (intermediary, mapped, descriptor)
+            }
+            (Some(intermediary), Some(descriptor), None) => {
+                (intermediary, intermediary, descriptor)
+            }
+            _ => return Err(MappingsError::bad_line(line, "missing descriptor")),
+        };
+    check_descriptor(descriptor).map_err(|e| MappingsError::bad_line(line, e.to_string()))?;
+    Ok((intermediary_name, mapped_name, descriptor))
 }
 
 fn parse_class_inner(
@@ -674,30 +1957,36 @@ fn parse_class_inner(
     parent: Option<&Class>,
     lines: &mut Peekable<Lines>,
     mappings: &mut Mappings,
-) {
-    let mut line = lines.next().unwrap().split(" ");
-    let count = line.clone().count();
-    line.next();
-    let (mut intermediary_name, mut mapped_name) = if count == 3 {
-        (
-            line.next().unwrap().to_string(),
-            line.next().unwrap().to_string(),
-        )
-    } else {
-        let intermediary = line.next().unwrap().to_string();
-        (intermediary.clone(), intermediary)
+) -> Result<(), MappingsError> {
+    let Some(line) = lines.next() else {
+        return Ok(());
     };
+    let mut columns = line.trim().split(' ');
+    let (mut intermediary_name, mut mapped_name) =
+        match (columns.next(), columns.next(), columns.next()) {
+            (Some("CLASS"), Some(intermediary), Some(mapped)) => {
+                (intermediary.to_string(), mapped.to_string())
+            }
+            (Some("CLASS"), Some(intermediary), None) => {
+                (intermediary.to_string(), intermediary.to_string())
+            }
+            _ => return Err(MappingsError::bad_line(line, "expected a class")),
+        };
     if let Some(parent) = parent {
-        intermediary_name = parent.intermediary_name.clone() + "$" + &intermediary_name;
-        mapped_name = parent.mapped_name.clone() + "$" + &mapped_name;
+        intermediary_name = parent.intermediary_name().to_string() + "$" + &intermediary_name;
+        mapped_name = parent.mapped_name().to_string() + "$" + &mapped_name;
     }
 
     let class_comments = parse_comments(lines);
 
     let mut class = Class {
-        intermediary_name,
-        mapped_name,
+        names: enigma_names(intermediary_name, mapped_name),
+        super_class: None,
+        interfaces: vec![],
+        signatures: Names::default(),
         comments: class_comments,
+        modifiers: 0,
+        modifiers_known: false,
         fields: HashMap::new(),
         methods: HashMap::new(),
         methods_nosig: HashMap::new(),
@@ -708,82 +1997,41 @@ fn parse_class_inner(
         if !line.starts_with(&"\t".repeat(indent_level.into())) {
             break;
         }
-        let (ltype, line) = line.trim().split_once(" ").unwrap();
+        let line = *line;
+        let (ltype, entry) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
         match ltype {
             "FIELD" => {
-                let mut inner = line.splitn(3, " ");
-                let count = inner.clone().count();
+                let (intermediary_name, mapped_name, descriptor) = parse_entry(line, entry)?;
                 lines.next();
-
-                let intermediary_name = inner.next().unwrap().to_string();
-                let mapped_name = if count == 3 {
-                    inner.next().unwrap().to_string()
-                } else {
-                    intermediary_name.clone()
-                };
-
-                class.fields.insert(
-                    to_rust_convention(&mapped_name),
-                    Field {
-                        intermediary_name,
-                        mapped_name,
-                        field_type: inner.next().unwrap().to_string(),
-                        mapped_field_type: String::new(),
-                        comments: parse_comments(lines),
-                        modifiers: 0,
-                    },
-                );
+                class.insert_field(Field {
+                    names: enigma_names(intermediary_name.to_string(), mapped_name.to_string()),
+                    descriptors: enigma_descriptor(descriptor.to_string()),
+                    signatures: Names::default(),
+                    comments: parse_comments(lines),
+                    modifiers: 0,
+                });
             }
             "METHOD" => {
-                let mut inner = line.splitn(3, " ");
-                let count = inner.clone().count();
+                let (intermediary_name, mapped_name, descriptor) = parse_entry(line, entry)?;
                 lines.next();
-
-                let intermediary_name = inner.next().unwrap().to_string();
-                let mapped_name = if count == 3 {
-                    inner.next().unwrap().to_string()
-                } else {
-                    intermediary_name.clone()
-                };
-
-                let signature = inner.next().expect(line).to_string();
-
-                let class_sig = format!("{}#{}", mapped_name.clone(), signature);
-                if class.methods_nosig.contains_key(&mapped_name) {
-                    class
-                        .methods_nosig
-                        .insert(mapped_name.clone(), "".to_string());
-                } else {
-                    class
-                        .methods_nosig
-                        .insert(mapped_name.clone(), class_sig.clone());
-                }
-
-                class.methods.insert(
-                    class_sig,
-                    Method {
-                        intermediary_name,
-                        mapped_name,
-                        signature,
-                        mapped_signature: String::new(),
-                        comments: parse_comments(lines),
-                        args: parse_method_args(lines),
-                        modifiers: 0,
-                    },
-                );
+                class.insert_method(Method {
+                    names: enigma_names(intermediary_name.to_string(), mapped_name.to_string()),
+                    descriptors: enigma_descriptor(descriptor.to_string()),
+                    signatures: Names::default(),
+                    comments: parse_comments(lines),
+                    args: parse_method_args(lines)?,
+                    modifiers: 0,
+                });
             }
             "CLASS" => {
-                parse_class_inner(indent_level + 1, Some(&class), lines, mappings);
+                parse_class_inner(indent_level + 1, Some(&class), lines, mappings)?;
             }
-            s => unreachable!("unknown pattern: '{}\' with line '{}'", s, line),
+            _ => return Err(MappingsError::bad_line(line, "unknown entry")),
         }
     }
 
-    mappings.insert_class(
-        class.mapped_name.clone(),
-        class.intermediary_name.clone(),
-        class,
-    );
+    mappings.insert_class(class);
+    Ok(())
 }
 
 fn visit_dirs<T: FnMut(&DirEntry)>(dir: &Path, cb: &mut T) -> io::Result<()> {
@@ -801,14 +2049,15 @@ fn visit_dirs<T: FnMut(&DirEntry)>(dir: &Path, cb: &mut T) -> io::Result<()> {
     Ok(())
 }
 
-fn replace_mappings(mappings: &HashMap<String, String>, t: &String) -> String {
+/// Rewrite every class name of a descriptor, classes unknown to `f` are kept as is
+fn map_descriptor_classes<'a>(t: &str, mut f: impl FnMut(&str) -> Option<&'a str>) -> String {
     let mut in_type = false;
     let mut tmp_type = String::new();
     let mut result = String::new();
     for letter in t.chars() {
         if in_type {
             if letter == ';' {
-                result += mappings.get(&tmp_type).unwrap_or(&tmp_type);
+                result += f(&tmp_type).unwrap_or(&tmp_type);
 
                 result.write_char(';').unwrap();
                 in_type = false;
@@ -826,79 +2075,344 @@ fn replace_mappings(mappings: &HashMap<String, String>, t: &String) -> String {
     result
 }
 
-pub fn set_mappings(mappings: &Mappings) {
-    let current_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
-    fs::write(
-        current_dir.join("mappings_cache.bin"),
-        bitcode::encode(mappings),
-    )
-    .unwrap();
+fn replace_mappings(mappings: &HashMap<String, String>, t: &str) -> String {
+    map_descriptor_classes(t, |class| mappings.get(class).map(|s| s.as_str()))
 }
-pub fn parse_mappings() -> Mappings {
-    // Check if a serialized file exists
-    let current_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
-    if let Ok(cache) = fs::read(current_dir.join("mappings_cache.bin")) {
-        return bitcode::decode(&cache).unwrap();
-    }
-    let mut mappings = Mappings::new();
-    let mappings_dir = current_dir.join("yarn/mappings/");
-    visit_dirs(&mappings_dir, &mut |dir| {
-        let mut file = File::open(dir.path()).unwrap();
-        let mut content = String::new();
-        file.read_to_string(&mut content).unwrap();
-        parse_class_inner(1, None, &mut content.lines().peekable(), &mut mappings);
-    })
-    .unwrap();
-
-    for value in mappings.mapped_map.values_mut() {
-        for field in value.fields.values_mut() {
-            field.mapped_field_type =
-                replace_mappings(&mut mappings.intermediary_map, &field.field_type);
-        }
-
-        let mut new_methods = HashMap::new();
-        for key in value.methods.keys() {
-            let mut method = value.methods.get(key).unwrap().clone();
-            method.mapped_signature =
-                replace_mappings(&mut mappings.intermediary_map, &method.signature);
-            new_methods.insert(
-                key.split_once("#")
-                    .map(|(start, sig)| {
-                        format!(
-                            "{}#{}",
-                            start,
-                            replace_mappings(&mut mappings.intermediary_map, &sig.to_string())
-                        )
-                    })
-                    .unwrap(),
-                method,
-            );
-        }
-        value.methods = new_methods;
-        for method in value.methods_nosig.values_mut() {
-            if !method.is_empty() {
-                *method = method
-                    .split_once("#")
-                    .map(|(start, sig)| {
+
+/// Lookup of classes by name, from the whole `Mappings` or from the `MappingsIndex` read by proc
+/// macros
+pub trait ClassLookup {
+    /// Find a class by its name in any namespace
+    fn get_opt(&self, name: &str) -> Option<&Class>;
+
+    /// Names of every class in every namespace
+    fn class_names(&self) -> Vec<&str>;
+
+    fn class(&self, name: &str) -> Result<&Class, MappingsError> {
+        self.get_opt(name)
+            .ok_or_else(|| MappingsError::unknown_class(name, self.class_names()))
+    }
+
+    /// `class` followed by its super classes, then by every interface they implement
+    fn ancestors<'a>(&'a self, class: &'a Class) -> Vec<&'a Class> {
+        let mut result = vec![class];
+        let mut current = class;
+        while let Some(super_class) = current.super_class.as_ref().and_then(|c| self.get_opt(c)) {
+            result.push(super_class);
+            current = super_class;
+        }
+        let mut i = 0;
+        while i < result.len() {
+            for interface in result[i].interfaces.iter() {
+                if let Some(interface) = self.get_opt(interface) {
+                    if !result.iter().any(|c| std::ptr::eq(*c, interface)) {
+                        result.push(interface);
+                    }
+                }
+            }
+            i += 1;
+        }
+        result
+    }
+
+    /// Closest ancestor declaring methods matching `selector`, with those methods
+    fn declaring_methods<'a>(
+        &'a self,
+        class: &'a Class,
+        selector: &str,
+    ) -> Option<(&'a Class, Vec<&'a Method>)> {
+        self.ancestors(class)
+            .into_iter()
+            .map(|class| (class, class.select_methods(selector)))
+            .find(|(_, methods)| !methods.is_empty())
+    }
+
+    /// Methods matching `selector` in the closest ancestor declaring one, see `Class::select_methods`
+    fn select_methods<'a>(&'a self, class: &'a Class, selector: &str) -> Vec<&'a Method> {
+        self.declaring_methods(class, selector)
+            .map(|(_, methods)| methods)
+            .unwrap_or_default()
+    }
+
+    fn find_method<'a>(&'a self, class: &'a Class, method: &str) -> Option<&'a Method> {
+        match self.select_methods(class, method).as_slice() {
+            [method] => Some(method),
+            _ => None,
+        }
+    }
+
+    fn find_field<'a>(&'a self, class: &'a Class, field: &str) -> Option<&'a Field> {
+        self.declaring_field(class, field).map(|(_, field)| field)
+    }
+
+    /// Closest ancestor declaring `field`, with that field
+    fn declaring_field<'a>(
+        &'a self,
+        class: &'a Class,
+        field: &str,
+    ) -> Option<(&'a Class, &'a Field)> {
+        self.ancestors(class)
+            .into_iter()
+            .find_map(|class| Some((class, class.get_declared_field(field)?)))
+    }
+
+    /// Like `find_method`, suggesting methods of the ancestors of `class` when none matches
+    fn method<'a>(&'a self, class: &'a Class, selector: &str) -> Result<&'a Method, MappingsError> {
+        self.method_declaration(class, selector)
+            .map(|(_, method)| method)
+    }
+
+    /// Like `method`, with the ancestor of `class` declaring the method
+    fn method_declaration<'a>(
+        &'a self,
+        class: &'a Class,
+        selector: &str,
+    ) -> Result<(&'a Class, &'a Method), MappingsError> {
+        let (owner, methods) = self
+            .declaring_methods(class, selector)
+            .unwrap_or((class, vec![]));
+        match methods.as_slice() {
+            [method] => Ok((owner, method)),
+            [] => {
+                let ancestors = self.ancestors(class);
+                let methods = ancestors.iter().flat_map(|class| class.methods.values());
+                Err(MappingsError::unknown_member(
+                    class.mapped_name(),
+                    selector,
+                    methods.flat_map(|method| member_names(&method.names)),
+                ))
+            }
+            overloads => Err(MappingsError::AmbiguousOverload {
+                class: class.mapped_name().to_string(),
+                method: selector.to_string(),
+                overloads: overloads
+                    .iter()
+                    .map(|method| {
                         format!(
                             "{}#{}",
-                            start,
-                            replace_mappings(&mut mappings.intermediary_map, &sig.to_string())
+                            method.mapped_name(),
+                            method.descriptor(Namespace::NAMED)
                         )
                     })
-                    .unwrap();
+                    .collect(),
+            }),
+        }
+    }
+
+    /// Like `find_field`, suggesting fields of the ancestors of `class` when none matches
+    fn field<'a>(&'a self, class: &'a Class, field: &str) -> Result<&'a Field, MappingsError> {
+        self.field_declaration(class, field).map(|(_, field)| field)
+    }
+
+    /// Like `field`, with the ancestor of `class` declaring the field
+    fn field_declaration<'a>(
+        &'a self,
+        class: &'a Class,
+        field: &str,
+    ) -> Result<(&'a Class, &'a Field), MappingsError> {
+        self.declaring_field(class, field).ok_or_else(|| {
+            let ancestors = self.ancestors(class);
+            let fields = ancestors.iter().flat_map(|class| class.fields.iter());
+            MappingsError::unknown_member(
+                class.mapped_name(),
+                field,
+                fields.flat_map(|(key, field)| {
+                    std::iter::once(key.as_str()).chain(member_names(&field.names))
+                }),
+            )
+        })
+    }
+}
+
+/// Named and intermediary names of a member, the ones suggested when a lookup fails
+fn member_names(names: &Names) -> impl Iterator<Item = &str> {
+    [Namespace::NAMED, Namespace::INTERMEDIARY]
+        .into_iter()
+        .filter_map(|ns| names.get(ns))
+}
+
+impl ClassLookup for Mappings {
+    fn get_opt(&self, name: &str) -> Option<&Class> {
+        Mappings::get_opt(self, name)
+    }
+
+    fn class_names(&self) -> Vec<&str> {
+        let names = self.class_names.iter().flat_map(|names| names.keys());
+        self.mapped_map
+            .keys()
+            .chain(names)
+            .map(|name| name.as_str())
+            .collect()
+    }
+}
+
+/// Replace the cache of the current mappings, and its index, and record their key for proc macros
+pub fn set_mappings(mappings: &Mappings) {
+    let key = CacheKey::current();
+    write_mappings(key, mappings);
+    key.record();
+}
+
+/// Build step run before the proc macros, by the build script of `rustcraft_codegen`. Parses and
+/// caches the current mappings unless they already are, then records their key, so proc macros
+/// and `rustcraft_mappings_gen` find the cache without hashing its inputs again
+pub fn prepare_mappings() -> CacheKey {
+    let key = CacheKey::current();
+    if !cache::exists(key) {
+        write_mappings(key, &parse_source());
+    }
+    key.record();
+    key
+}
+
+/// Replace the cache of `key`, and its index
+pub fn write_mappings(key: CacheKey, mappings: &Mappings) {
+    cache::write(key, mappings);
+    MappingsIndex::write(mappings, &key.index_path());
+}
+/// Where mappings are read from when no cache is available
+pub enum MappingsSource {
+    /// A yarn checkout, `yarn/mappings/` directory of Enigma files
+    Enigma(PathBuf),
+    /// A Tiny v2 file, as shipped in yarn jars (`mappings/mappings.tiny`)
+    Tiny(PathBuf),
+    /// A yarn or intermediary jar containing a Tiny v2 file
+    Jar(PathBuf),
+}
+
+impl MappingsSource {
+    /// Use `RUSTCRAFT_MAPPINGS` if set, then a `mappings.tiny` file next to this crate, then the yarn
+    /// checkout, and finally the jars downloaded by loom in the gradle cache
+    pub fn detect() -> Self {
+        let current_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        if let Ok(path) = env::var("RUSTCRAFT_MAPPINGS") {
+            return Self::from_path(path);
+        }
+        let tiny = current_dir.join("mappings.tiny");
+        if tiny.is_file() {
+            return MappingsSource::Tiny(tiny);
+        }
+        let yarn = current_dir.join("yarn/mappings/");
+        let yarn_checked_out = fs::read_dir(&yarn).is_ok_and(|mut dir| dir.next().is_some());
+        if !yarn_checked_out {
+            if let Some(jar) = jar::find_mappings_jar() {
+                return MappingsSource::Jar(jar);
+            }
+        }
+        MappingsSource::Enigma(yarn)
+    }
+
+    /// File or directory the mappings are read from
+    pub fn path(&self) -> &Path {
+        match self {
+            MappingsSource::Enigma(path)
+            | MappingsSource::Tiny(path)
+            | MappingsSource::Jar(path) => path,
+        }
+    }
+
+    pub fn from_path<P: Into<PathBuf>>(path: P) -> Self {
+        let path = path.into();
+        if path.is_dir() {
+            MappingsSource::Enigma(path)
+        } else if path.extension().is_some_and(|ext| ext == "jar") {
+            MappingsSource::Jar(path)
+        } else {
+            MappingsSource::Tiny(path)
+        }
+    }
+
+    /// Mappings completed by the mojang mappings and by the game jar when found
+    pub fn parse(&self) -> Result<Mappings, MappingsError> {
+        let mut mappings = self.read()?;
+        if let Ok(path) = env::var("RUSTCRAFT_MOJANG_MAPPINGS") {
+            let content = fs::read_to_string(&path)
+                .unwrap_or_else(|e| panic!("Can't read mojang mappings {:?}: {}", path, e));
+            mojang::parse_proguard(&content, &mut mappings)
+                .map_err(|e| e.in_file(Path::new(&path)))?;
+        }
+        mappings.complete_signatures();
+        if let Some(jar) = jar::find_minecraft_jar() {
+            let classes = jar::read_classes(&jar);
+            mappings.complete_hierarchy(&classes);
+            mappings.complete_modifiers(&classes);
+            mappings.complete_generics(&classes);
+        }
+        Ok(mappings)
+    }
+
+    /// Mappings of the source only, without mojang names nor data of the game jar, which belong to
+    /// the current Minecraft version. Used to compare mappings of several versions
+    pub fn parse_raw(&self) -> Result<Mappings, MappingsError> {
+        let mut mappings = self.read()?;
+        mappings.complete_signatures();
+        Ok(mappings)
+    }
+
+    fn read(&self) -> Result<Mappings, MappingsError> {
+        let mut mappings = Mappings::new();
+        match self {
+            MappingsSource::Enigma(dir) => {
+                let mut result = Ok(());
+                visit_dirs(dir, &mut |entry| {
+                    if result.is_err() {
+                        return;
+                    }
+                    let path = entry.path();
+                    let content = fs::read_to_string(&path)
+                        .unwrap_or_else(|e| panic!("Can't read mappings {:?}: {}", path, e));
+                    result =
+                        parse_class_inner(1, None, &mut content.lines().peekable(), &mut mappings)
+                            .map_err(|e| e.in_file(&path));
+                })
+                .unwrap();
+                result?;
+            }
+            MappingsSource::Tiny(file) => {
+                let content = fs::read_to_string(file)
+                    .unwrap_or_else(|e| panic!("Can't read tiny mappings {:?}: {}", file, e));
+                tiny::parse_tiny(&content, &mut mappings).map_err(|e| e.in_file(file))?;
+            }
+            MappingsSource::Jar(jar) => {
+                tiny::parse_tiny(&jar::read_tiny_from_jar(jar), &mut mappings)
+                    .map_err(|e| e.in_file(jar))?;
             }
         }
+        Ok(mappings)
     }
+}
+
+pub fn parse_mappings() -> Mappings {
+    read_mappings(CacheKey::current())
+}
+
+/// Mappings cached under `key`, parsed from `MappingsSource::detect` and cached when missing
+pub fn read_mappings(key: CacheKey) -> Mappings {
+    // Check if an up to date cache exists
+    if let Some(mappings) = cache::read(key) {
+        return mappings;
+    }
+    let mappings = parse_source();
 
     // Add mappings to cache
-    set_mappings(&mappings);
+    write_mappings(key, &mappings);
 
     mappings
 }
 
-pub fn get_class(class: &str) -> &Class {
-    MAPPINGS.get(class)
+fn parse_source() -> Mappings {
+    MappingsSource::detect()
+        .parse()
+        .unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Classes looked up by proc macros, decoded from the index on their first use
+pub fn classes() -> &'static dyn ClassLookup {
+    &*INDEX
+}
+
+pub fn get_class(class: &str) -> Result<&'static Class, MappingsError> {
+    classes().class(class)
 }
 
 pub struct ClassGetter<'a> {
@@ -906,20 +2420,254 @@ pub struct ClassGetter<'a> {
 }
 
 impl<'a> ClassGetter<'a> {
-    pub fn get_method(&self, method: &str) -> Option<&Method> {
-        self.classes
+    /// Method of the first class having one matching `method`, the error of the first class
+    /// otherwise
+    pub fn get_method(&self, method: &str) -> Result<&Method, MappingsError> {
+        let mut first_error = None;
+        for class in self.classes.iter() {
+            match classes().method(class, method) {
+                Ok(method) => return Ok(method),
+                Err(error) => {
+                    first_error.get_or_insert(error);
+                }
+            }
+        }
+        Err(first_error.unwrap_or_else(|| MappingsError::unknown_member("", method, [])))
+    }
+}
+pub fn get_multiple_class<'a>(class: &'a [&'a str]) -> Result<ClassGetter<'a>, MappingsError> {
+    Ok(ClassGetter {
+        classes: class
             .iter()
-            .skip_while(|class| class.get_method(method).is_none())
-            .next()
-            .map(|class| class.get_method(method).unwrap())
+            .map(|class| get_class(class))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// Translate a descriptor written with class names of any namespace to the `TARGET` namespace.
+/// Classes outside of `net/minecraft/` are kept as is, unknown ones inside are an error
+pub fn convert_sig(sig: &str) -> Result<String, MappingsError> {
+    check_descriptor(sig)?;
+    let mut unknown = None;
+    let result = map_descriptor_classes(sig, |class| {
+        let found = classes().get_opt(class);
+        if found.is_none() && class.starts_with("net/minecraft/") {
+            unknown.get_or_insert_with(|| class.to_string());
+        }
+        found.map(|class| class.get_java_name())
+    });
+    match unknown {
+        Some(class) => Err(MappingsError::unknown_class(
+            &class,
+            classes().class_names(),
+        )),
+        None => Ok(result),
+    }
+}
+
+/// Check the syntax of a method or field descriptor
+pub(crate) fn check_descriptor(descriptor: &str) -> Result<(), MappingsError> {
+    let mut chars = descriptor.chars().peekable();
+    let is_method = chars.next_if_eq(&'(').is_some();
+    if is_method {
+        while chars.next_if_eq(&')').is_none() {
+            if chars.peek().is_none() {
+                return Err(MappingsError::bad_descriptor(descriptor, "missing `)`"));
+            }
+            check_field_type(descriptor, &mut chars, false)?;
+        }
+    }
+    check_field_type(descriptor, &mut chars, is_method)?;
+    match chars.next() {
+        Some(_) => Err(MappingsError::bad_descriptor(
+            descriptor,
+            "unexpected characters after the type",
+        )),
+        None => Ok(()),
     }
 }
-pub fn get_multiple_class<'a>(class: &'a [&'a str]) -> ClassGetter<'a> {
-    ClassGetter {
-        classes: class.iter().map(|class| get_class(class)).collect(),
+
+fn check_field_type(
+    descriptor: &str,
+    chars: &mut Peekable<Chars>,
+    void_allowed: bool,
+) -> Result<(), MappingsError> {
+    match chars.next() {
+        Some('B' | 'C' | 'D' | 'F' | 'I' | 'J' | 'S' | 'Z') => Ok(()),
+        Some('V') if void_allowed => Ok(()),
+        Some('[') => check_field_type(descriptor, chars, false),
+        Some('L') => match chars.position(|c| c == ';') {
+            Some(0) => Err(MappingsError::bad_descriptor(
+                descriptor,
+                "empty class name",
+            )),
+            Some(_) => Ok(()),
+            None => Err(MappingsError::bad_descriptor(
+                descriptor,
+                "missing `;` after a class name",
+            )),
+        },
+        Some(c) => Err(MappingsError::bad_descriptor(
+            descriptor,
+            format!("unexpected `{}`", c),
+        )),
+        None => Err(MappingsError::bad_descriptor(descriptor, "missing a type")),
     }
 }
 
-pub fn convert_sig(sig: &str) -> String {
-    replace_mappings(&MAPPINGS.intermediary_map, &sig.to_string())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TINY: &str = "tiny\t2\t0\tofficial\tintermediary\tnamed
+c\ta\tnet/minecraft/class_1\tnet/minecraft/block/Block
+\tf\tI\tb\tfield_1\tluminance
+\tf\tI\tc\tfield_2\thardness
+";
+
+    const ENUM: &str = "tiny\t2\t0\tofficial\tintermediary\tnamed
+c\ta\tnet/minecraft/class_1\tnet/minecraft/util/Direction
+\tf\tLa;\tb\tfield_10\tWEST
+\tf\tLa;\tc\tfield_9\tEAST
+\tf\t[La;\td\tfield_11\tALL
+\tf\tI\te\tfield_12\tid
+";
+
+    #[test]
+    fn enums() {
+        let mut mappings = Mappings::new();
+        tiny::parse_tiny(ENUM, &mut mappings).unwrap();
+        let class = mappings
+            .mapped_map
+            .get_mut("net/minecraft/util/Direction")
+            .unwrap();
+        assert!(!class.is_enum());
+        class.super_class = Some("java/lang/Enum".to_string());
+        assert!(class.is_enum());
+        let constants: Vec<&str> = class
+            .enum_constants()
+            .into_iter()
+            .map(|(_, field)| field.mapped_name())
+            .collect();
+        assert_eq!(constants, ["EAST", "WEST"]);
+        // Only the static array of the constants is synthetic
+        class.modifiers_known = true;
+        assert!(!class.is_enum());
+        let values = class
+            .fields
+            .values_mut()
+            .find(|field| field.mapped_name() == "ALL");
+        values.unwrap().modifiers = Modifier::Static as u8;
+        assert!(class.is_enum());
+    }
+
+    const OVERLOADS: &str = "tiny\t2\t0\tofficial\tintermediary\tnamed
+c\ta\tnet/minecraft/class_1\tnet/minecraft/world/World
+\tm\t()V\t<init>\t<init>\t<init>
+\tm\t(I)V\t<init>\t<init>\t<init>
+\tm\t()V\tb\tmethod_1\ttick
+\tm\t(Lb;)Z\tc\tmethod_2\tsetBlockState
+\tm\t(Lb;I)Z\tc\tmethod_3\tsetBlockState
+\tm\t()Z\td\tmethod_4\tsetBlockState
+\tm\t()Ljava/lang/Object;\te\tmethod_5\tgetValue
+\tm\t()Lb;\te\tmethod_5\tgetValue
+c\tb\tnet/minecraft/class_2\tnet/minecraft/util/math/BlockPos
+";
+
+    fn overload_names() -> Vec<(String, String)> {
+        let mut mappings = Mappings::new();
+        tiny::parse_tiny(OVERLOADS, &mut mappings).unwrap();
+        mappings.complete_signatures();
+        let class = &mappings.mapped_map["net/minecraft/world/World"];
+        let mut names: Vec<(String, String)> = class
+            .method_rust_names()
+            .into_iter()
+            .map(|(key, name)| (key.to_string(), name))
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn method_rust_names() {
+        let names = overload_names();
+        let expected = [
+            ("<init>#()V", "new_default"),
+            ("<init>#(I)V", "new_i32"),
+            ("getValue#()Ljava/lang/Object;", "get_value"),
+            // Bridge methods only differ by their return type
+            (
+                "getValue#()Lnet/minecraft/util/math/BlockPos;",
+                "get_value_2",
+            ),
+            ("setBlockState#()Z", "set_block_state"),
+            (
+                "setBlockState#(Lnet/minecraft/util/math/BlockPos;)Z",
+                "set_block_state_block_pos",
+            ),
+            (
+                "setBlockState#(Lnet/minecraft/util/math/BlockPos;I)Z",
+                "set_block_state_block_pos_i32",
+            ),
+            ("tick#()V", "tick"),
+        ];
+        let expected: Vec<(String, String)> = expected
+            .iter()
+            .map(|(key, name)| (key.to_string(), name.to_string()))
+            .collect();
+        assert_eq!(names, expected);
+        let unique: HashSet<&String> = names.iter().map(|(_, name)| name).collect();
+        assert_eq!(unique.len(), names.len());
+        // Maps are iterated in another order by each parse
+        for _ in 0..8 {
+            assert_eq!(overload_names(), names);
+        }
+    }
+
+    /// Names of the setters generated for the fields of `TINY`, `luminance` being final
+    fn setters(modifiers_known: bool) -> Vec<String> {
+        let mut mappings = Mappings::new();
+        tiny::parse_tiny(TINY, &mut mappings).unwrap();
+        mappings.complete_signatures();
+        let class = mappings
+            .mapped_map
+            .get_mut("net/minecraft/block/Block")
+            .unwrap();
+        class.modifiers_known = modifiers_known;
+        let luminance = class
+            .fields
+            .values_mut()
+            .find(|field| field.mapped_name() == "luminance");
+        luminance.unwrap().modifiers = Modifier::Final as u8;
+        let class = &mappings.mapped_map["net/minecraft/block/Block"];
+        let mut setters: Vec<String> = class
+            .sorted_fields()
+            .into_iter()
+            .map(|field| {
+                field
+                    .to_tokens(class.get_java_name(), class, &[])
+                    .to_string()
+            })
+            .flat_map(|tokens| {
+                let words: Vec<String> = tokens.split(' ').map(str::to_string).collect();
+                let functions: Vec<String> = words
+                    .windows(2)
+                    .filter(|pair| pair[0] == "fn")
+                    .map(|pair| pair[1].clone())
+                    .collect();
+                functions
+                    .into_iter()
+                    .filter(|name| name.starts_with("set_"))
+            })
+            .collect();
+        setters.sort();
+        setters
+    }
+
+    #[test]
+    fn final_fields_have_no_setter() {
+        assert_eq!(setters(true), ["set_hardness"]);
+        // Any field may be final
+        assert!(setters(false).is_empty());
+    }
 }
@@ -1,7 +1,321 @@
-use rustcraft_mappings::{get_class, MAPPINGS};
+//! Query the mappings from the command line, see `USAGE`
+use std::{env, error::Error, fs, path::Path, process::ExitCode};
 
-fn main() {
-    println!("{:#?}", get_class("net/minecraft/block/Block"));
-    // println!("{:#?}", get_class("net/minecraft/registry/Registry"));
-    // println!("{:#?}", MAPPINGS);
+use regex::Regex;
+use rustcraft_mappings::{
+    diff, enigma::write_enigma, error::suggestions, json::write_json, migrate::Migration,
+    tiny::write_tiny, Class, ClassLookup, Field, MappingsSource, Method, Modifier, Names,
+    Namespace, MAPPINGS,
+};
+
+const USAGE: &str = "\
+Usage: rustcraft_mappings <command>
+
+Commands:
+  lookup <name>                        Class named <name>, or every method and field named <name>
+  lookup <class> <member>              Method or field of <class> or of its ancestors, methods being
+                                       selected like `getBlockState(BlockPos)` if overloaded
+  descriptor <descriptor> <from> <to>  Translate the classes of a descriptor between namespaces
+  search <regex>                       Methods and fields having a name matching <regex>
+  rust-path <class>                    Path of the Rust type generated for <class>
+  diff <old> <new> [--json]            Classes, methods and fields added, removed, renamed or with
+                                       a changed descriptor between two mappings, given as paths
+                                       to tiny files, yarn jars or Enigma directories
+  migrate <old> <new> <dir> [--write]  Patch renaming the mapped names used by the Rust sources of
+                                       <dir> from <old> to <new> mappings, applied with --write
+  export <format> <out> [<mappings>]   Write mappings as a `tiny` v2 file, an `enigma` directory or
+                                       a `json` file. The current mappings are written by default,
+                                       with the modifiers completed by rustcraft_mappings_gen
+
+Names are accepted in any namespace, like `net/minecraft/class_2338`, `method_10263` or
+`net/minecraft/util/math/BlockPos`.";
+
+type Result<T = ()> = std::result::Result<T, Box<dyn Error>>;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let result = match args.as_slice() {
+        ["lookup", name] => lookup(name),
+        ["lookup", class, member] => lookup_member(class, member),
+        ["descriptor", descriptor, from, to] => translate_descriptor(descriptor, from, to),
+        ["search", pattern] => search(pattern),
+        ["rust-path", class] => rust_path(class),
+        ["diff", old, new] => diff(old, new, false),
+        ["diff", old, new, "--json"] => diff(old, new, true),
+        ["migrate", old, new, dir] => migrate(old, new, dir, false),
+        ["migrate", old, new, dir, "--write"] => migrate(old, new, dir, true),
+        ["export", format, out] => export(format, out, None),
+        ["export", format, out, mappings] => export(format, out, Some(mappings)),
+        ["help"] | ["--help"] | ["-h"] => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn lookup(name: &str) -> Result {
+    if let Some(class) = MAPPINGS.get_opt(name) {
+        print_class(class);
+        return Ok(());
+    }
+    let mut found = false;
+    for class in sorted_classes() {
+        for method in sorted_methods(class) {
+            if method.names.iter().any(|(_, n)| n == name) {
+                print_method(class, method);
+                found = true;
+            }
+        }
+        for field in sorted_fields(class) {
+            if field.names.iter().any(|(_, n)| n == name) {
+                print_field(class, field);
+                found = true;
+            }
+        }
+    }
+    if found {
+        return Ok(());
+    }
+    let members = MAPPINGS.mapped_map.values().flat_map(|class| {
+        let methods = class.methods.values().map(|method| &method.names);
+        methods.chain(class.fields.values().map(|field| &field.names))
+    });
+    let candidates = MAPPINGS
+        .class_names()
+        .into_iter()
+        .chain(members.flat_map(|names| names.iter().map(|(_, name)| name)));
+    let mut message = format!("No class, method or field named {}", name);
+    if let [first, others @ ..] = suggestions(name, candidates).as_slice() {
+        message += &format!(", did you mean {}", first);
+        for other in others {
+            message += &format!(", {}", other);
+        }
+        message += "?";
+    }
+    Err(message.into())
+}
+
+fn lookup_member(class: &str, member: &str) -> Result {
+    let class = MAPPINGS.class(class)?;
+    match MAPPINGS.method_declaration(class, member) {
+        Ok((owner, method)) => print_method(owner, method),
+        Err(method_error) => match MAPPINGS.field_declaration(class, member) {
+            Ok((owner, field)) => print_field(owner, field),
+            // Ambiguous overloads tell more than a missing field
+            Err(_) => return Err(method_error.into()),
+        },
+    }
+    Ok(())
+}
+
+fn translate_descriptor(descriptor: &str, from: &str, to: &str) -> Result {
+    let (from, to) = (namespace(from)?, namespace(to)?);
+    println!("{}", MAPPINGS.translate_descriptor(descriptor, from, to));
+    Ok(())
+}
+
+fn namespace(name: &str) -> Result<Namespace> {
+    MAPPINGS.namespace(name).ok_or_else(|| {
+        let namespaces = MAPPINGS.namespaces.join(", ");
+        format!("Unknown namespace {}, expected one of {}", name, namespaces).into()
+    })
+}
+
+fn search(pattern: &str) -> Result {
+    let regex = Regex::new(pattern)?;
+    let matches = |names: &Names| names.iter().any(|(_, name)| regex.is_match(name));
+    for class in sorted_classes() {
+        for method in sorted_methods(class).filter(|method| matches(&method.names)) {
+            println!(
+                "{}.{}{} {}",
+                class.mapped_name(),
+                method.mapped_name(),
+                method.descriptor(Namespace::NAMED),
+                other_names(&method.names)
+            );
+        }
+        for field in sorted_fields(class).filter(|field| matches(&field.names)) {
+            println!(
+                "{}.{}: {} {}",
+                class.mapped_name(),
+                field.mapped_name(),
+                field.descriptor(Namespace::NAMED),
+                other_names(&field.names)
+            );
+        }
+    }
+    Ok(())
+}
+
+fn rust_path(class: &str) -> Result {
+    let class = MAPPINGS.class(class)?;
+    println!("{}", class.rust_path());
+    Ok(())
+}
+
+fn diff(old: &str, new: &str, json: bool) -> Result {
+    let old = MappingsSource::from_path(old).parse_raw()?;
+    let new = MappingsSource::from_path(new).parse_raw()?;
+    let diff = diff::diff(&old, &new);
+    if json {
+        println!("{}", diff.to_json());
+    } else {
+        print!("{}", diff);
+    }
+    Ok(())
+}
+
+fn migrate(old: &str, new: &str, dir: &str, write: bool) -> Result {
+    let old = MappingsSource::from_path(old).parse_raw()?;
+    let new = MappingsSource::from_path(new).parse_raw()?;
+    let migration = Migration::new(&old, &diff::diff(&old, &new));
+    let dir = Path::new(dir);
+    for file in migration.migrate_dir(dir, write)? {
+        if write {
+            println!("Migrated {}", file.path.display());
+        } else {
+            print!("{}", file.patch(dir));
+        }
+    }
+    Ok(())
+}
+
+fn export(format: &str, out: &str, mappings: Option<&str>) -> Result {
+    let parsed;
+    let mappings = match mappings {
+        Some(path) => {
+            parsed = MappingsSource::from_path(path).parse()?;
+            &parsed
+        }
+        None => &*MAPPINGS,
+    };
+    match format {
+        "tiny" => fs::write(out, write_tiny(mappings))?,
+        "enigma" => write_enigma(mappings, Path::new(out))?,
+        "json" => fs::write(out, write_json(mappings))?,
+        _ => {
+            return Err(format!("Unknown format {}, expected tiny, enigma or json", format).into())
+        }
+    }
+    Ok(())
+}
+
+fn print_class(class: &Class) {
+    println!("class {}", class.mapped_name());
+    print_names(&class.names, |_| "");
+    print_modifiers(class.modifiers);
+    if let Some(super_class) = class.super_class.as_ref() {
+        println!("  extends {}", mapped_name(super_class));
+    }
+    for interface in class.interfaces.iter() {
+        println!("  implements {}", mapped_name(interface));
+    }
+    println!("  rust: {}", class.rust_path());
+    print_comments(&class.comments);
+    for method in sorted_methods(class) {
+        println!(
+            "  method {}{} {}",
+            method.mapped_name(),
+            method.descriptor(Namespace::NAMED),
+            other_names(&method.names)
+        );
+    }
+    for field in sorted_fields(class) {
+        println!(
+            "  field {}: {} {}",
+            field.mapped_name(),
+            field.descriptor(Namespace::NAMED),
+            other_names(&field.names)
+        );
+    }
+}
+
+fn print_method(class: &Class, method: &Method) {
+    println!("method {}.{}", class.mapped_name(), method.mapped_name());
+    print_names(&method.names, |ns| method.descriptor(ns));
+    print_modifiers(method.modifiers);
+    for arg in method.args.iter() {
+        print!("  arg {} {}", arg.position, arg.name);
+        if Modifier::Nullable & arg.modifiers {
+            print!(" (nullable)");
+        }
+        println!();
+        print_comments(&arg.comment);
+    }
+    print_comments(&method.comments);
+}
+
+fn print_field(class: &Class, field: &Field) {
+    println!("field {}.{}", class.mapped_name(), field.mapped_name());
+    print_names(&field.names, |ns| field.descriptor(ns));
+    print_modifiers(field.modifiers);
+    print_comments(&field.comments);
+}
+
+/// Name in every namespace, followed by its descriptor
+fn print_names<'a>(names: &'a Names, descriptor: impl Fn(Namespace) -> &'a str) {
+    for (ns, name) in names.iter() {
+        let namespace = MAPPINGS
+            .namespaces
+            .get(ns.index())
+            .map_or("?", String::as_str);
+        let line = format!("  {}: {} {}", namespace, name, descriptor(ns));
+        println!("{}", line.trim_end());
+    }
+}
+
+fn print_modifiers(modifiers: u8) {
+    let names = Modifier::names(modifiers);
+    if !names.is_empty() {
+        println!("  modifiers: {}", names.join(" "));
+    }
+}
+
+fn print_comments(comments: &str) {
+    for line in comments.lines() {
+        println!("    // {}", line);
+    }
+}
+
+/// Intermediary and official names between brackets, the named one being printed before
+fn other_names(names: &Names) -> String {
+    let others: Vec<&str> = [Namespace::INTERMEDIARY, Namespace::OFFICIAL]
+        .into_iter()
+        .filter_map(|ns| names.get(ns))
+        .collect();
+    format!("[{}]", others.join(", "))
+}
+
+fn mapped_name(class: &str) -> &str {
+    MAPPINGS.get_opt(class).map_or(class, Class::mapped_name)
+}
+
+fn sorted_classes() -> Vec<&'static Class> {
+    let mut classes: Vec<&Class> = MAPPINGS.mapped_map.values().collect();
+    classes.sort_by_key(|class| class.mapped_name());
+    classes
+}
+
+fn sorted_methods(class: &Class) -> impl Iterator<Item = &Method> {
+    let mut methods: Vec<(&String, &Method)> = class.methods.iter().collect();
+    methods.sort_by_key(|(key, _)| *key);
+    methods.into_iter().map(|(_, method)| method)
+}
+
+fn sorted_fields(class: &Class) -> impl Iterator<Item = &Field> {
+    let mut fields: Vec<&Field> = class.fields.values().collect();
+    fields.sort_by_key(|field| field.mapped_name());
+    fields.into_iter()
 }
@@ -0,0 +1,577 @@
+//! Rewrite the mapped names used by a mod after a Minecraft version bump, from the renames of a
+//! `MappingsDiff`. Sources are scanned as text for:
+//! - string literals of `mappings!`, `mappings_with_sig!`, `mappings_sig!`, `mappings_class!`,
+//!   `#[to_java]` and `#[to_java_method]`
+//! - the block trait of `register_block!`, named after its class like `Block`. Its block id isn't
+//!   a mapped name
+//! - paths of generated types, like `net::minecraft::block::AbstractBlock`
+//! - methods of `#[block]` implementations, named after the methods of `Block`
+//!
+//! Every name is looked up in the original source and replaced at once, so chained renames like
+//! `A` to `B` and `B` to `C` aren't applied twice. Methods called on generated types can't be
+//! resolved without type checking, they are left as is
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    fs, io,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    diff::{ChangeKind, MappingsDiff},
+    java_to_rust_method, rust_path, visit_dirs, ClassLookup, Mappings, Namespace,
+};
+
+/// Classes whose methods `#[block]` overrides, explicit as mappings compared across versions don't
+/// have the hierarchy read from the game jar
+const BLOCK_CLASSES: [&str; 2] = [
+    "net/minecraft/block/Block",
+    "net/minecraft/block/AbstractBlock",
+];
+
+/// What an argument of a macro holds
+#[derive(Clone, Copy)]
+enum Argument {
+    /// String literal of a class name
+    Class,
+    /// String literal of a method selector, see `Class::select_methods`, its class being the
+    /// previous argument
+    Member,
+    /// String literal of a descriptor
+    Descriptor,
+    /// Block trait, named after the simple name of one of `BLOCK_CLASSES`
+    BlockTrait,
+    /// Argument without mapped names
+    Other,
+}
+
+/// Macros and attributes taking mapped names, `!` telling macros from attributes
+const MACROS: [(&str, &[Argument]); 7] = [
+    ("mappings!", &[Argument::Class, Argument::Member]),
+    ("mappings_with_sig!", &[Argument::Class, Argument::Member]),
+    ("mappings_sig!", &[Argument::Descriptor]),
+    ("mappings_class!", &[Argument::Class]),
+    ("to_java", &[Argument::Class]),
+    ("to_java_method", &[Argument::Class]),
+    (
+        "register_block!",
+        &[
+            Argument::Other,
+            Argument::Other,
+            Argument::Other,
+            Argument::BlockTrait,
+        ],
+    ),
+];
+
+/// Named names renamed between two mappings
+#[derive(Debug, Default)]
+pub struct Renames {
+    /// Old class names to new ones
+    pub classes: HashMap<String, String>,
+    /// Old names of members and of their class to new member names
+    pub members: HashMap<(String, String), String>,
+}
+
+impl Renames {
+    pub fn new(diff: &MappingsDiff) -> Self {
+        let mut renames = Renames::default();
+        let renamed = diff
+            .changes
+            .iter()
+            .filter(|change| change.kind == ChangeKind::Renamed);
+        for change in renamed {
+            let (Some(old), Some(new)) = (&change.old, &change.new) else {
+                continue;
+            };
+            match (&old.member, &new.member) {
+                (None, None) => {
+                    renames.classes.insert(old.class.clone(), new.class.clone());
+                }
+                (Some(old_member), Some(new_member)) => {
+                    renames
+                        .members
+                        .insert((old.class.clone(), old_member.clone()), new_member.clone());
+                }
+                _ => {}
+            }
+        }
+        renames
+    }
+}
+
+/// Source file changed by a migration
+pub struct MigratedFile {
+    pub path: PathBuf,
+    pub original: String,
+    pub migrated: String,
+}
+
+impl MigratedFile {
+    /// Unified diff without context lines, paths being relative to `root`. Applied from `root` by
+    /// `git apply --unidiff-zero`
+    pub fn patch(&self, root: &Path) -> String {
+        let path = self.path.strip_prefix(root).unwrap_or(&self.path);
+        let path = path.to_string_lossy();
+        let mut patch = format!("--- a/{}\n+++ b/{}\n", path, path);
+        let original: Vec<&str> = self.original.lines().collect();
+        let migrated: Vec<&str> = self.migrated.lines().collect();
+        // Names are replaced inside lines, so lines match one to one
+        let mut i = 0;
+        while i < original.len() {
+            if original[i] == migrated[i] {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            while i < original.len() && original[i] != migrated[i] {
+                i += 1;
+            }
+            let len = i - start;
+            writeln!(patch, "@@ -{},{} +{},{} @@", start + 1, len, start + 1, len).unwrap();
+            for line in &original[start..i] {
+                writeln!(patch, "-{}", line).unwrap();
+            }
+            for line in &migrated[start..i] {
+                writeln!(patch, "+{}", line).unwrap();
+            }
+        }
+        patch
+    }
+}
+
+/// Rewrites sources written against the `old` mappings of a diff
+pub struct Migration<'a> {
+    old: &'a Mappings,
+    renames: Renames,
+    /// Old and new paths of the generated types of renamed classes, from `net::`
+    paths: Vec<(String, String)>,
+    /// Old and new Rust names of the renamed methods of `BLOCK_CLASSES` and their ancestors
+    block_methods: Vec<(String, String)>,
+    /// Old and new simple names of the renamed `BLOCK_CLASSES`
+    block_traits: Vec<(String, String)>,
+}
+
+impl<'a> Migration<'a> {
+    pub fn new(old: &'a Mappings, diff: &MappingsDiff) -> Self {
+        let renames = Renames::new(diff);
+        let module_path = |class: &str| rust_path(class).trim_start_matches("crate::").to_string();
+        let paths = renames
+            .classes
+            .iter()
+            .map(|(old, new)| (module_path(old), module_path(new)))
+            .collect();
+        let block_ancestors: Vec<&str> = BLOCK_CLASSES
+            .iter()
+            .filter_map(|class| old.get_opt(class))
+            .flat_map(|block| old.ancestors(block))
+            .map(|class| class.name(Namespace::NAMED))
+            .collect();
+        let block_methods = renames
+            .members
+            .iter()
+            .filter(|((class, _), _)| block_ancestors.contains(&class.as_str()))
+            .map(|((_, old), new)| (java_to_rust_method(old), java_to_rust_method(new)))
+            .collect();
+        let block_traits = BLOCK_CLASSES
+            .iter()
+            .filter_map(|class| Some((*class, renames.classes.get(*class)?)))
+            .map(|(old, new)| (simple_name(old).to_string(), simple_name(new).to_string()))
+            .collect();
+        Migration {
+            old,
+            renames,
+            paths,
+            block_methods,
+            block_traits,
+        }
+    }
+
+    /// `source` with renamed names replaced
+    pub fn migrate_source(&self, source: &str) -> String {
+        let mut edits: Vec<(Range<usize>, String)> = vec![];
+        for (name, kinds) in MACROS {
+            for arguments in macro_arguments(source, name) {
+                let values: Vec<&str> = arguments
+                    .iter()
+                    .map(|range| &source[range.clone()])
+                    .collect();
+                for (i, (range, kind)) in arguments.iter().zip(kinds).enumerate() {
+                    let value = values[i];
+                    if let Argument::BlockTrait = kind {
+                        let renamed = self.block_traits.iter().find(|(old, _)| old == value);
+                        if let Some((_, new)) = renamed {
+                            edits.push((range.clone(), new.clone()));
+                        }
+                        continue;
+                    }
+                    let Some(value) = string_content(value) else {
+                        continue;
+                    };
+                    let replacement = match kind {
+                        Argument::Class => self.renames.classes.get(value).cloned(),
+                        Argument::Member => match i.checked_sub(1).map(|i| values[i]) {
+                            Some(class) => string_content(class)
+                                .map(|class| self.migrate_selector(class, value)),
+                            None => None,
+                        },
+                        Argument::Descriptor => Some(self.migrate_types(value)),
+                        Argument::BlockTrait | Argument::Other => None,
+                    };
+                    if let Some(replacement) = replacement.filter(|r| r != value) {
+                        edits.push((range.start + 1..range.end - 1, replacement));
+                    }
+                }
+            }
+        }
+        for (old, new) in self.paths.iter() {
+            edits.extend(word_edits(source, old, new));
+        }
+        if source.contains("#[block]") {
+            for (old, new) in self.block_methods.iter() {
+                let (old, new) = (format!("fn {}", old), format!("fn {}", new));
+                edits.extend(word_edits(source, &old, &new));
+            }
+        }
+        apply_edits(source, edits)
+    }
+
+    /// Migrate every `.rs` file of `dir` and its subdirectories, `target` directories excepted.
+    /// Changed files are returned, and written if `write` is set
+    pub fn migrate_dir(&self, dir: &Path, write: bool) -> io::Result<Vec<MigratedFile>> {
+        let mut paths = vec![];
+        visit_dirs(dir, &mut |entry| {
+            let path = entry.path();
+            let in_target = path
+                .strip_prefix(dir)
+                .is_ok_and(|path| path.components().any(|c| c.as_os_str() == "target"));
+            if path.extension().is_some_and(|ext| ext == "rs") && !in_target {
+                paths.push(path);
+            }
+        })?;
+        paths.sort();
+        let mut files = vec![];
+        for path in paths {
+            let original = fs::read_to_string(&path)?;
+            let migrated = self.migrate_source(&original);
+            if migrated == original {
+                continue;
+            }
+            if write {
+                fs::write(&path, &migrated)?;
+            }
+            files.push(MigratedFile {
+                path,
+                original,
+                migrated,
+            });
+        }
+        Ok(files)
+    }
+
+    /// Rename the method of a selector, and the classes of its parameters
+    fn migrate_selector(&self, class: &str, selector: &str) -> String {
+        let (name, parameters) = match selector.find('(') {
+            Some(i) => selector.split_at(i),
+            None => (selector, ""),
+        };
+        let ancestors = self
+            .old
+            .get_opt(class)
+            .map(|class| self.old.ancestors(class))
+            .unwrap_or_default();
+        let name = ancestors
+            .iter()
+            .find_map(|class| {
+                let key = (class.name(Namespace::NAMED).to_string(), name.to_string());
+                self.renames.members.get(&key)
+            })
+            .map_or(name, String::as_str);
+        format!("{}{}", name, self.migrate_types(parameters))
+    }
+
+    /// Rename classes of a descriptor, or simple names of Java types like `(BlockPos, int)`
+    fn migrate_types(&self, types: &str) -> String {
+        let mut edits = vec![];
+        for (old, new) in self.renames.classes.iter() {
+            if types.contains('/') {
+                let (old, new) = (format!("L{};", old), format!("L{};", new));
+                let matches = types.match_indices(&old).map(|(i, _)| i..i + old.len());
+                edits.extend(matches.map(|range| (range, new.clone())));
+            } else {
+                edits.extend(word_edits(types, simple_name(old), simple_name(new)));
+            }
+        }
+        apply_edits(types, edits)
+    }
+}
+
+fn simple_name(class: &str) -> &str {
+    class.rsplit(['/', '$']).next().unwrap()
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Replacements of the occurrences of `from` which aren't part of a longer identifier
+fn word_edits<'a>(
+    s: &'a str,
+    from: &'a str,
+    to: &'a str,
+) -> impl Iterator<Item = (Range<usize>, String)> + 'a {
+    s.match_indices(from).filter_map(move |(i, _)| {
+        let end = i + from.len();
+        let before = s[..i].chars().next_back().is_some_and(is_ident_char);
+        let after = s[end..].chars().next().is_some_and(is_ident_char);
+        (!before && !after).then(|| (i..end, to.to_string()))
+    })
+}
+
+/// Apply edits of ranges of `s` at once, so replaced text is never replaced again. Of overlapping
+/// edits, the one starting first is kept, the longest one when they start together
+fn apply_edits(s: &str, mut edits: Vec<(Range<usize>, String)>) -> String {
+    edits.sort_by(|(a, a_text), (b, b_text)| {
+        (a.start, b.end, a_text).cmp(&(b.start, a.end, b_text))
+    });
+    let mut result = String::with_capacity(s.len());
+    let mut last = 0;
+    for (range, replacement) in edits {
+        if range.start < last {
+            continue;
+        }
+        result.push_str(&s[last..range.start]);
+        result.push_str(&replacement);
+        last = range.end;
+    }
+    result.push_str(&s[last..]);
+    result
+}
+
+/// Content of a string literal, `None` for other expressions
+fn string_content(argument: &str) -> Option<&str> {
+    argument.strip_prefix('"')?.strip_suffix('"')
+}
+
+/// Ranges of the arguments of each use of the macro or attribute `name`, without surrounding
+/// whitespace. Attributes are only found after `#[`
+fn macro_arguments(source: &str, name: &str) -> Vec<Vec<Range<usize>>> {
+    let mut result = vec![];
+    for (i, _) in source.match_indices(name) {
+        let end = i + name.len();
+        let before = source[..i].chars().next_back().is_some_and(is_ident_char);
+        let after = source[end..].chars().next().is_some_and(is_ident_char);
+        if before || after {
+            continue;
+        }
+        if !name.ends_with('!') && !source[..i].trim_end().ends_with("#[") {
+            continue;
+        }
+        let arguments = source[end..].trim_start();
+        if !arguments.starts_with('(') {
+            continue;
+        }
+        let start = source.len() - arguments.len() + 1;
+        result.push(arguments_from(source, start));
+    }
+    result
+}
+
+/// Ranges of the comma separated arguments found from `start` to the parenthesis closing them
+fn arguments_from(source: &str, start: usize) -> Vec<Range<usize>> {
+    let mut arguments = vec![];
+    let mut push = |range: Range<usize>| {
+        let argument = &source[range.clone()];
+        let trimmed = argument.trim_start();
+        let start = range.start + argument.len() - trimmed.len();
+        let end = start + trimmed.trim_end().len();
+        if start < end {
+            arguments.push(start..end);
+        }
+    };
+    let mut depth = 0;
+    let mut argument_start = start;
+    let mut chars = source[start..].char_indices().map(|(i, c)| (start + i, c));
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' if depth == 0 => {
+                push(argument_start..i);
+                break;
+            }
+            ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                push(argument_start..i);
+                argument_start = i + 1;
+            }
+            '"' => {
+                let mut escaped = false;
+                for (_, c) in chars.by_ref() {
+                    match c {
+                        '"' if !escaped => break,
+                        '\\' => escaped = !escaped,
+                        _ => escaped = false,
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    arguments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{diff::diff, tiny::parse_tiny};
+
+    /// `BlockPos` becomes `Vec3i` while `Vec3i` becomes `Position`, and `Block` is renamed too
+    const OLD: &str = "tiny\t2\t0\tofficial\tintermediary\tnamed
+c\ta\tnet/minecraft/class_1\tnet/minecraft/block/Block
+\tm\t()V\tb\tmethod_1\tonSteppedOn
+\tm\t(Ld;)V\tc\tmethod_2\tneighborUpdate
+c\td\tnet/minecraft/class_2\tnet/minecraft/util/math/BlockPos
+c\te\tnet/minecraft/class_3\tnet/minecraft/util/math/Vec3i
+";
+    const NEW: &str = "tiny\t2\t0\tofficial\tintermediary\tnamed
+c\ta\tnet/minecraft/class_1\tnet/minecraft/block/BaseBlock
+\tm\t()V\tb\tmethod_1\tonStep
+\tm\t(Ld;)V\tc\tmethod_2\tneighborUpdate
+c\td\tnet/minecraft/class_2\tnet/minecraft/util/math/Vec3i
+c\te\tnet/minecraft/class_3\tnet/minecraft/util/math/Position
+";
+
+    fn mappings(content: &str) -> Mappings {
+        let mut mappings = Mappings::new();
+        parse_tiny(content, &mut mappings).unwrap();
+        mappings.complete_signatures();
+        mappings
+    }
+
+    fn migrate(source: &str) -> String {
+        let old = mappings(OLD);
+        let diff = diff(&old, &mappings(NEW));
+        Migration::new(&old, &diff).migrate_source(source)
+    }
+
+    fn replace_words(s: &str, renames: &[(&str, &str)]) -> String {
+        let edits = renames
+            .iter()
+            .flat_map(|(from, to)| word_edits(s, from, to))
+            .collect();
+        apply_edits(s, edits)
+    }
+
+    #[test]
+    fn whole_words() {
+        assert_eq!(
+            replace_words("Block BlockPos a::Block(Block)", &[("Block", "Base")]),
+            "Base BlockPos a::Base(Base)"
+        );
+        // Replacements are never replaced again
+        assert_eq!(replace_words("A B", &[("A", "B"), ("B", "A")]), "B A");
+        // The longest of overlapping matches wins
+        assert_eq!(
+            replace_words("a::B::C", &[("a::B", "x"), ("a::B::C", "y")]),
+            "y"
+        );
+    }
+
+    #[test]
+    fn arguments() {
+        let source = r#"mappings!("a", "b(int, (x))") #[to_java("c")] x.to_java("d") to_java_method("e")
+register_block!(api, Dynamic { a: (1, 2) }, "dynamic", Block);"#;
+        let values = |name| -> Vec<Vec<&str>> {
+            macro_arguments(source, name)
+                .into_iter()
+                .map(|ranges| ranges.into_iter().map(|range| &source[range]).collect())
+                .collect()
+        };
+        assert_eq!(values("mappings!"), [[r#""a""#, r#""b(int, (x))""#]]);
+        // Calls aren't attributes
+        assert_eq!(values("to_java"), [[r#""c""#]]);
+        assert!(values("to_java_method").is_empty());
+        assert_eq!(
+            values("register_block!"),
+            [["api", "Dynamic { a: (1, 2) }", r#""dynamic""#, "Block"]]
+        );
+    }
+
+    #[test]
+    fn chained_renames() {
+        let source = r#"let a = mappings_class!("net/minecraft/util/math/BlockPos");
+let b = mappings_class!("net/minecraft/util/math/Vec3i");
+let c: net::minecraft::util::math::BlockPos = d::<net::minecraft::util::math::Vec3i>();
+"#;
+        assert_eq!(
+            migrate(source),
+            r#"let a = mappings_class!("net/minecraft/util/math/Vec3i");
+let b = mappings_class!("net/minecraft/util/math/Position");
+let c: net::minecraft::util::math::Vec3i = d::<net::minecraft::util::math::Position>();
+"#
+        );
+    }
+
+    #[test]
+    fn descriptors_and_selectors() {
+        assert_eq!(
+            migrate(
+                r#"mappings_sig!("(Lnet/minecraft/util/math/BlockPos;Lnet/minecraft/util/math/Vec3i;)V")"#
+            ),
+            r#"mappings_sig!("(Lnet/minecraft/util/math/Vec3i;Lnet/minecraft/util/math/Position;)V")"#
+        );
+        assert_eq!(
+            migrate(r#"mappings!("net/minecraft/block/Block", "neighborUpdate(BlockPos, Vec3i)")"#),
+            r#"mappings!("net/minecraft/block/BaseBlock", "neighborUpdate(Vec3i, Position)")"#
+        );
+        assert_eq!(
+            migrate(r#"mappings_with_sig!("net/minecraft/block/Block", "onSteppedOn")"#),
+            r#"mappings_with_sig!("net/minecraft/block/BaseBlock", "onStep")"#
+        );
+    }
+
+    #[test]
+    fn blocks() {
+        let source = r#"#[block]
+impl Block for Dynamic {
+    fn on_stepped_on(&self) {}
+}
+register_block!(api, block, "dynamic", Block);
+x.to_java("net/minecraft/block/Block");
+"#;
+        assert_eq!(
+            migrate(source),
+            r#"#[block]
+impl Block for Dynamic {
+    fn on_step(&self) {}
+}
+register_block!(api, block, "dynamic", BaseBlock);
+x.to_java("net/minecraft/block/Block");
+"#
+        );
+    }
+
+    #[test]
+    fn patch() {
+        let file = MigratedFile {
+            path: PathBuf::from("/mod/src/lib.rs"),
+            original: "a\nb\nc\nd\ne\n".to_string(),
+            migrated: "a\nB\nC\nd\nE\n".to_string(),
+        };
+        assert_eq!(
+            file.patch(Path::new("/mod")),
+            "--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -2,2 +2,2 @@
+-b
+-c
++B
++C
+@@ -5,1 +5,1 @@
+-e
++E
+"
+        );
+    }
+}
@@ -0,0 +1,300 @@
+use std::collections::HashMap;
+
+use crate::{replace_mappings, Mappings, MappingsError, Namespace};
+
+/// A class of a ProGuard file, members are keyed by official name and official descriptor
+#[derive(Default)]
+struct ProguardClass {
+    mojang_name: String,
+    fields: HashMap<(String, String), String>,
+    methods: HashMap<(String, String), String>,
+}
+
+fn java_type_to_descriptor(java_type: &str, classes: &HashMap<String, String>) -> String {
+    let mut result = String::new();
+    let mut java_type = java_type;
+    while let Some(inner) = java_type.strip_suffix("[]") {
+        result.push('[');
+        java_type = inner;
+    }
+    match java_type {
+        "boolean" => result.push('Z'),
+        "byte" => result.push('B'),
+        "char" => result.push('C'),
+        "short" => result.push('S'),
+        "int" => result.push('I'),
+        "long" => result.push('J'),
+        "float" => result.push('F'),
+        "double" => result.push('D'),
+        "void" => result.push('V'),
+        object => {
+            let object = object.replace('.', "/");
+            result.push('L');
+            result += classes.get(&object).unwrap_or(&object);
+            result.push(';');
+        }
+    }
+    result
+}
+
+/// Member of a ProGuard class, the part of its line before ` -> `
+enum Member<'a> {
+    /// `type name`
+    Field { field_type: &'a str, name: &'a str },
+    /// `[start:end:]type name(args)[:originalStart[:originalEnd]]`. Names of methods inlined from
+    /// other classes are qualified by their class
+    Method {
+        lines: Option<&'a str>,
+        ret: &'a str,
+        name: &'a str,
+        args: &'a str,
+    },
+}
+
+fn is_number(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_digit())
+}
+
+/// `None` if `member` is malformed
+fn parse_member(member: &str) -> Option<Member<'_>> {
+    let Some((start, rest)) = member.split_once('(') else {
+        let (field_type, name) = member.split_once(' ')?;
+        return Some(Member::Field { field_type, name });
+    };
+    let (args, original_lines) = rest.split_once(')')?;
+    if !original_lines.is_empty() {
+        let original_lines = original_lines.strip_prefix(':')?;
+        if !original_lines.split(':').all(is_number) {
+            return None;
+        }
+    }
+    let (lines, ret_and_name) = match start.splitn(3, ':').collect::<Vec<_>>()[..] {
+        [first, last, ret_and_name] if is_number(first) && is_number(last) => {
+            (Some(&start[..first.len() + last.len() + 1]), ret_and_name)
+        }
+        _ => (None, start),
+    };
+    let (ret, name) = ret_and_name.split_once(' ')?;
+    Some(Member::Method {
+        lines,
+        ret,
+        name,
+        args,
+    })
+}
+
+/// Method of a ProGuard class whose name is only known once the next line is read
+struct Pending {
+    lines: String,
+    obfuscated: String,
+    key: (String, String),
+    name: String,
+}
+
+/// Parse a Mojang `client.txt`/`server.txt` file, classes are keyed by official name.
+///
+/// The lines of a method body inlined into another one are listed before the lines of the caller,
+/// with the same line range and obfuscated name. Only the last method of such a run is the one
+/// the obfuscated name belongs to
+fn parse_classes(content: &str) -> Result<HashMap<String, ProguardClass>, MappingsError> {
+    // Mojang names to official ones, needed to build official descriptors
+    let mut class_names = HashMap::new();
+    for line in content.lines() {
+        if line.starts_with('#') || line.starts_with(' ') {
+            continue;
+        }
+        if let Some((mojang, official)) = line.trim_end_matches(':').split_once(" -> ") {
+            class_names.insert(mojang.replace('.', "/"), official.replace('.', "/"));
+        }
+    }
+
+    let mut classes: HashMap<String, ProguardClass> = HashMap::new();
+    let mut current = None;
+    let mut pending: Option<Pending> = None;
+    for line in content.lines() {
+        if line.trim_start().starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        let Some((left, official)) = line.trim().split_once(" -> ") else {
+            continue;
+        };
+        if !line.starts_with(' ') {
+            if let (Some(method), Some(class)) = (pending.take(), current.as_ref()) {
+                classes
+                    .get_mut(class)
+                    .unwrap()
+                    .methods
+                    .insert(method.key, method.name);
+            }
+            let mojang_name = left.replace('.', "/");
+            let official = official.trim_end_matches(':').replace('.', "/");
+            classes.entry(official.clone()).or_default().mojang_name = mojang_name;
+            current = Some(official);
+            continue;
+        }
+        let Some(class) = current.as_ref().and_then(|c| classes.get_mut(c)) else {
+            continue;
+        };
+        let member =
+            parse_member(left).ok_or_else(|| MappingsError::bad_line(line, "invalid member"))?;
+        let (lines, ret, name, args) = match member {
+            Member::Field { field_type, name } => {
+                class.fields.insert(
+                    (
+                        official.to_string(),
+                        java_type_to_descriptor(field_type, &class_names),
+                    ),
+                    name.to_string(),
+                );
+                continue;
+            }
+            Member::Method {
+                lines,
+                ret,
+                name,
+                args,
+            } => (lines, ret, name, args),
+        };
+        // The pending method was inlined into this one
+        let inlined = pending.as_ref().is_some_and(|pending| {
+            lines == Some(pending.lines.as_str()) && official == pending.obfuscated
+        });
+        if let Some(method) = pending.take().filter(|_| !inlined) {
+            class.methods.insert(method.key, method.name);
+        }
+        // Methods of other classes are only found inlined
+        if name.contains('.') {
+            continue;
+        }
+        let mut descriptor = String::from("(");
+        for arg in args.split(',').filter(|arg| !arg.is_empty()) {
+            descriptor += &java_type_to_descriptor(arg, &class_names);
+        }
+        descriptor.push(')');
+        descriptor += &java_type_to_descriptor(ret, &class_names);
+        let key = (official.to_string(), descriptor);
+        match lines {
+            Some(lines) => {
+                pending = Some(Pending {
+                    lines: lines.to_string(),
+                    obfuscated: official.to_string(),
+                    key,
+                    name: name.to_string(),
+                })
+            }
+            None => {
+                class.methods.insert(key, name.to_string());
+            }
+        }
+    }
+    if let (Some(method), Some(class)) = (pending, current) {
+        classes
+            .get_mut(&class)
+            .unwrap()
+            .methods
+            .insert(method.key, method.name);
+    }
+    Ok(classes)
+}
+
+/// Add Mojang names from a ProGuard mappings file to the `mojang` namespace of `mappings`.
+///
+/// Mojang mappings are linked to the others through obfuscated names, so `mappings` must have been
+/// read from tiny mappings containing the `official` namespace.
+pub fn parse_proguard(content: &str, mappings: &mut Mappings) -> Result<(), MappingsError> {
+    let proguard = parse_classes(content)?;
+
+    let official_classes: HashMap<String, String> = mappings
+        .mapped_map
+        .values()
+        .filter_map(|class| {
+            class
+                .names
+                .get(Namespace::OFFICIAL)
+                .map(|official| (class.intermediary_name().to_string(), official.to_string()))
+        })
+        .collect();
+    if official_classes.is_empty() {
+        return Err(MappingsError::MissingNamespace {
+            namespace: "official".to_string(),
+            reason: "mojang mappings are linked through official names, use tiny mappings \
+                     instead of enigma ones"
+                .to_string(),
+        });
+    }
+    for class in mappings.mapped_map.values_mut() {
+        let Some(proguard_class) = class
+            .names
+            .get(Namespace::OFFICIAL)
+            .and_then(|official| proguard.get(official))
+        else {
+            continue;
+        };
+        class
+            .names
+            .set(Namespace::MOJANG, proguard_class.mojang_name.clone());
+
+        for field in class.fields.values_mut() {
+            let key = (
+                field.name(Namespace::OFFICIAL).to_string(),
+                replace_mappings(&official_classes, field.descriptor(Namespace::INTERMEDIARY)),
+            );
+            if let Some(name) = proguard_class.fields.get(&key) {
+                field.names.set(Namespace::MOJANG, name.clone());
+            }
+        }
+        for method in class.methods.values_mut() {
+            let key = (
+                method.name(Namespace::OFFICIAL).to_string(),
+                replace_mappings(
+                    &official_classes,
+                    method.descriptor(Namespace::INTERMEDIARY),
+                ),
+            );
+            if let Some(name) = proguard_class.methods.get(&key) {
+                method.names.set(Namespace::MOJANG, name.clone());
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inlined_methods() {
+        let classes = parse_classes(
+            "net.minecraft.world.level.block.Block -> dcs:
+    net.minecraft.world.level.block.Block DEFAULT -> b
+    1:5:void stepOn(net.minecraft.world.level.Level) -> a
+    6:6:void helper():10:10 -> e
+    6:6:int net.minecraft.world.level.Level.height():20 -> e
+    6:6:void updateShape(int):3 -> e
+    void foo() -> c
+net.minecraft.world.level.Level -> jx:
+    1:1:boolean isClientSide():5:5 -> a
+",
+        )
+        .unwrap();
+        let block = &classes["dcs"];
+        assert_eq!(block.mojang_name, "net/minecraft/world/level/block/Block");
+        let method = |name: &str, descriptor: &str| {
+            block
+                .methods
+                .get(&(name.to_string(), descriptor.to_string()))
+                .map(|name| name.as_str())
+        };
+        assert_eq!(method("a", "(Ljx;)V"), Some("stepOn"));
+        assert_eq!(method("e", "(I)V"), Some("updateShape"));
+        assert_eq!(method("e", "()V"), None);
+        assert_eq!(method("c", "()V"), Some("foo"));
+        let level = &classes["jx"];
+        assert_eq!(
+            level.methods[&("a".to_string(), "()Z".to_string())],
+            "isClientSide"
+        );
+        assert!(parse_classes("a.B -> c:\n    void foo( -> a\n").is_err());
+    }
+}
@@ -0,0 +1,65 @@
+use bitcode::{Decode, Encode};
+
+/// Index of a namespace in `Mappings::namespaces`
+#[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Namespace(pub u8);
+
+impl Namespace {
+    pub const OFFICIAL: Namespace = Namespace(0);
+    pub const INTERMEDIARY: Namespace = Namespace(1);
+    pub const NAMED: Namespace = Namespace(2);
+    pub const MOJANG: Namespace = Namespace(3);
+
+    /// Namespaces every `Mappings` starts with, extra ones are appended after them
+    pub const DEFAULTS: [&'static str; 4] = ["official", "intermediary", "named", "mojang"];
+
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Namespace of the game the bindings are built for, `named` and `mojang` cargo features select
+/// development environments, intermediary (production) is used otherwise
+pub const TARGET: Namespace = if cfg!(feature = "mojang") {
+    Namespace::MOJANG
+} else if cfg!(feature = "named") {
+    Namespace::NAMED
+} else {
+    Namespace::INTERMEDIARY
+};
+
+/// Name of an item in each namespace, empty when unknown
+#[derive(Encode, Decode, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Names(Vec<String>);
+
+impl Names {
+    pub fn get(&self, ns: Namespace) -> Option<&str> {
+        self.0
+            .get(ns.index())
+            .map(|s| s.as_str())
+            .filter(|s| !s.is_empty())
+    }
+
+    /// Name in `ns`, falling back to the intermediary one when unknown
+    pub fn name(&self, ns: Namespace) -> &str {
+        self.get(ns)
+            .or(self.get(Namespace::INTERMEDIARY))
+            .unwrap_or_default()
+    }
+
+    pub fn set(&mut self, ns: Namespace, name: String) {
+        if self.0.len() <= ns.index() {
+            self.0.resize(ns.index() + 1, String::new());
+        }
+        self.0[ns.index()] = name;
+    }
+
+    /// Known names with their namespace
+    pub fn iter(&self) -> impl Iterator<Item = (Namespace, &str)> {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(|(_, name)| !name.is_empty())
+            .map(|(i, name)| (Namespace(i as u8), name.as_str()))
+    }
+}
@@ -0,0 +1,522 @@
+use std::{cell::Cell, collections::HashMap, fmt::Write};
+
+use crate::{
+    check_descriptor, replace_mappings, Arg, Class, Field, Mappings, MappingsError, Method, Names,
+    Namespace,
+};
+
+struct Header {
+    namespaces: Vec<String>,
+    escaped_names: bool,
+}
+
+impl Header {
+    fn namespace(&self, name: &str) -> Option<usize> {
+        self.namespaces.iter().position(|ns| ns == name)
+    }
+}
+
+fn unescape(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some('t') => result.push('\t'),
+            Some('0') => result.push('\0'),
+            Some('\\') => result.push('\\'),
+            Some(c) => {
+                result.push('\\');
+                result.push(c);
+            }
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// Append the comment of a `c` line, repeated lines being separate lines of the comment like
+/// the `COMMENT` lines of Enigma files
+fn add_comment(comments: &mut String, comment: Option<&str>) {
+    if !comments.is_empty() {
+        comments.push('\n');
+    }
+    *comments += &unescape(comment.unwrap_or_default());
+}
+
+fn escape(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            '\0' => result.push_str("\\0"),
+            '\\' => result.push_str("\\\\"),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+/// Names of an entry in every namespace of the file, empty names are missing ones
+struct Columns(Vec<String>);
+
+impl Columns {
+    fn parse<'a>(header: &Header, columns: impl Iterator<Item = &'a str>) -> Self {
+        Columns(
+            columns
+                .map(|name| {
+                    if header.escaped_names {
+                        unescape(name)
+                    } else {
+                        name.to_string()
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    fn get(&self, ns: usize) -> Option<&str> {
+        self.0.get(ns).map(|s| s.as_str()).filter(|s| !s.is_empty())
+    }
+
+    /// Name in `ns`, or in `fallback` when missing
+    fn get_or(&self, ns: usize, fallback: usize) -> String {
+        self.get(ns)
+            .or(self.get(fallback))
+            .or(self.get(0))
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    /// Names of every column, `namespaces` being the namespace of each column in `Mappings`
+    fn names(&self, namespaces: &[Namespace]) -> Names {
+        let mut names = Names::default();
+        for (ns, name) in namespaces.iter().zip(self.0.iter()) {
+            names.set(*ns, name.clone());
+        }
+        names
+    }
+}
+
+fn parse_header(line: &str) -> Result<Header, MappingsError> {
+    let mut columns = line.split('\t');
+    match (columns.next(), columns.next(), columns.next()) {
+        (Some("tiny"), Some("2"), Some(_)) => {}
+        _ => {
+            return Err(MappingsError::bad_line(
+                line,
+                "only tiny v2 mappings are supported",
+            ))
+        }
+    }
+    Ok(Header {
+        namespaces: columns.map(|ns| ns.to_string()).collect(),
+        escaped_names: false,
+    })
+}
+
+fn to_intermediary(descriptor_map: &HashMap<String, String>, descriptors: &mut Names) {
+    let descriptor = replace_mappings(descriptor_map, descriptors.name(Namespace::INTERMEDIARY));
+    descriptors.set(Namespace::INTERMEDIARY, descriptor);
+}
+
+enum Member {
+    None,
+    Field(String),
+    Method(String),
+}
+
+/// Parse a Tiny v2 file, as found in yarn and intermediary jars, into `mappings`.
+///
+/// Descriptors are written in the first namespace of the file, they are translated to intermediary
+/// before being stored, so the result is the same as the one built from Enigma files. Unknown
+/// properties and sections are skipped with their children, as the format allows extending it
+pub fn parse_tiny(content: &str, mappings: &mut Mappings) -> Result<(), MappingsError> {
+    let mut lines = content.lines().peekable();
+    let mut header = parse_header(lines.next().unwrap_or_default())?;
+
+    // Properties
+    while let Some(line) = lines.peek() {
+        match line.strip_prefix('\t') {
+            Some(property) => {
+                if property.split('\t').next() == Some("escaped-names") {
+                    header.escaped_names = true;
+                }
+                lines.next();
+            }
+            None => break,
+        }
+    }
+
+    let Some(intermediary) = header.namespace("intermediary") else {
+        return Err(MappingsError::bad_line(
+            content.lines().next().unwrap_or_default(),
+            "no intermediary namespace",
+        ));
+    };
+    let named = header.namespace("named").unwrap_or(intermediary);
+    let namespaces: Vec<Namespace> = header
+        .namespaces
+        .iter()
+        .map(|ns| mappings.add_namespace(ns))
+        .collect();
+
+    let mut classes: Vec<Class> = vec![];
+    // Class names of the descriptors namespace to intermediary ones
+    let mut descriptor_map = HashMap::new();
+    let mut member = Member::None;
+    let mut arg: Option<usize> = None;
+    // Indentation of the unknown section being skipped
+    let mut skipped: Option<usize> = None;
+
+    for line in lines {
+        if line.is_empty() {
+            continue;
+        }
+        let indent = line.chars().take_while(|c| *c == '\t').count();
+        if skipped.is_some_and(|skipped| indent > skipped) {
+            continue;
+        }
+        skipped = None;
+        let mut columns = line[indent..].split('\t');
+        let kind = columns.next().unwrap_or_default();
+        let error = |reason: &str| MappingsError::bad_line(line, reason);
+
+        match (indent, kind) {
+            (0, "c") => {
+                let columns = Columns::parse(&header, columns);
+                descriptor_map.insert(
+                    columns.get_or(0, intermediary),
+                    columns.get_or(intermediary, 0),
+                );
+                classes.push(Class {
+                    names: columns.names(&namespaces),
+                    super_class: None,
+                    interfaces: vec![],
+                    signatures: Names::default(),
+                    comments: String::new(),
+                    modifiers: 0,
+                    modifiers_known: false,
+                    fields: HashMap::new(),
+                    methods_nosig: HashMap::new(),
+                    methods: HashMap::new(),
+                });
+                member = Member::None;
+            }
+            (1, "c") => {
+                let class = classes
+                    .last_mut()
+                    .ok_or_else(|| error("comment outside of a class"))?;
+                add_comment(&mut class.comments, columns.next());
+            }
+            (1, "f") | (1, "m") => {
+                let class = classes
+                    .last_mut()
+                    .ok_or_else(|| error("member outside of a class"))?;
+                let descriptor = columns.next().ok_or_else(|| error("missing descriptor"))?;
+                check_descriptor(descriptor).map_err(|e| error(&e.to_string()))?;
+                let mut descriptors = Names::default();
+                descriptors.set(Namespace::INTERMEDIARY, descriptor.to_string());
+                let columns = Columns::parse(&header, columns);
+                let names = columns.names(&namespaces);
+                let mapped_name = columns.get_or(named, intermediary);
+                if mapped_name.is_empty() {
+                    return Err(error("member without names"));
+                }
+                if kind == "f" {
+                    member = Member::Field(mapped_name.clone());
+                    class.fields.insert(
+                        mapped_name,
+                        Field {
+                            names,
+                            descriptors,
+                            signatures: Names::default(),
+                            comments: String::new(),
+                            modifiers: 0,
+                        },
+                    );
+                } else {
+                    let key = format!(
+                        "{}#{}",
+                        mapped_name,
+                        descriptors.name(Namespace::INTERMEDIARY)
+                    );
+                    member = Member::Method(key.clone());
+                    class.methods.insert(
+                        key,
+                        Method {
+                            names,
+                            descriptors,
+                            signatures: Names::default(),
+                            comments: String::new(),
+                            args: vec![],
+                            modifiers: 0,
+                        },
+                    );
+                }
+                arg = None;
+            }
+            (2, "c") => {
+                let class = classes
+                    .last_mut()
+                    .ok_or_else(|| error("comment outside of a class"))?;
+                let comments = match &member {
+                    Member::Field(name) => &mut class.fields.get_mut(name).unwrap().comments,
+                    Member::Method(key) => &mut class.methods.get_mut(key).unwrap().comments,
+                    Member::None => return Err(error("comment outside of a member")),
+                };
+                add_comment(comments, columns.next());
+            }
+            (2, "p") => {
+                let (Some(class), Member::Method(key)) = (classes.last_mut(), &member) else {
+                    return Err(error("parameter outside of a method"));
+                };
+                let position = columns
+                    .next()
+                    .and_then(|position| position.parse::<u16>().ok())
+                    .ok_or_else(|| error("invalid parameter index"))?;
+                let columns = Columns::parse(&header, columns);
+                let method = class.methods.get_mut(key).unwrap();
+                arg = Some(method.args.len());
+                method.args.push(Arg {
+                    position,
+                    name: columns.get_or(named, intermediary),
+                    comment: String::new(),
+                    modifiers: 0,
+                });
+            }
+            (3, "c") => {
+                if let (Some(class), Member::Method(key), Some(arg)) =
+                    (classes.last_mut(), &member, arg)
+                {
+                    let comment = &mut class.methods.get_mut(key).unwrap().args[arg].comment;
+                    add_comment(comment, columns.next());
+                }
+            }
+            // Local variables aren't used, other sections are unknown
+            (indent, _) => skipped = Some(indent),
+        }
+    }
+
+    for class in classes {
+        let mut result = Class {
+            fields: HashMap::new(),
+            methods_nosig: HashMap::new(),
+            methods: HashMap::new(),
+            ..class
+        };
+        for mut field in class.fields.into_values() {
+            if intermediary != 0 {
+                to_intermediary(&descriptor_map, &mut field.descriptors);
+            }
+            result.insert_field(field);
+        }
+        for mut method in class.methods.into_values() {
+            if intermediary != 0 {
+                to_intermediary(&descriptor_map, &mut method.descriptors);
+            }
+            method.args.sort_by_key(|arg| arg.position);
+            result.insert_method(method);
+        }
+        mappings.insert_class(result);
+    }
+    Ok(())
+}
+
+/// Write `mappings` as a Tiny v2 file, which `parse_tiny` reads back.
+///
+/// Only namespaces naming at least one class are written, descriptors being in the first of them.
+/// Names are escaped, with the `escaped-names` property, only when one of them needs it.
+/// Data read from the game jar, like hierarchy, generic signatures and modifiers, has no place in
+/// the format and is left out, see `json::write_json` to keep it
+pub fn write_tiny(mappings: &Mappings) -> String {
+    let namespaces: Vec<Namespace> = (0..mappings.namespaces.len())
+        .map(|i| Namespace(i as u8))
+        .filter(|ns| mappings.class_names[ns.index()].values().next().is_some())
+        .collect();
+    let descriptor_ns = namespaces
+        .first()
+        .copied()
+        .unwrap_or(Namespace::INTERMEDIARY);
+    let escaped_names = Cell::new(false);
+    let names = |names: &Names| -> String {
+        let columns: Vec<String> = namespaces
+            .iter()
+            .map(|ns| {
+                let name = names.get(*ns).unwrap_or_default();
+                let escaped = escape(name);
+                if escaped != name {
+                    escaped_names.set(true);
+                }
+                escaped
+            })
+            .collect();
+        columns.join("\t")
+    };
+    let descriptor = |descriptors: &Names| {
+        let descriptor = descriptors.name(Namespace::INTERMEDIARY);
+        mappings.translate_descriptor(descriptor, Namespace::INTERMEDIARY, descriptor_ns)
+    };
+    let comment = |result: &mut String, indent: usize, comment: &str| {
+        if !comment.is_empty() {
+            let indent = "\t".repeat(indent);
+            writeln!(result, "{}c\t{}", indent, escape(comment)).unwrap();
+        }
+    };
+
+    let header: Vec<&str> = namespaces
+        .iter()
+        .map(|ns| mappings.namespaces[ns.index()].as_str())
+        .collect();
+    let mut result = String::new();
+    for class in mappings.sorted_classes() {
+        writeln!(result, "c\t{}", names(&class.names)).unwrap();
+        comment(&mut result, 1, &class.comments);
+        for field in class.sorted_fields() {
+            let descriptor = descriptor(&field.descriptors);
+            writeln!(result, "\tf\t{}\t{}", descriptor, names(&field.names)).unwrap();
+            comment(&mut result, 2, &field.comments);
+        }
+        for method in class.sorted_methods() {
+            let descriptor = descriptor(&method.descriptors);
+            writeln!(result, "\tm\t{}\t{}", descriptor, names(&method.names)).unwrap();
+            comment(&mut result, 2, &method.comments);
+            for arg in method.args.iter() {
+                // Argument names are only known in the named namespace
+                let mut arg_names = Names::default();
+                arg_names.set(Namespace::NAMED, arg.name.clone());
+                writeln!(result, "\t\tp\t{}\t{}", arg.position, names(&arg_names)).unwrap();
+                comment(&mut result, 3, &arg.comment);
+            }
+        }
+    }
+    let properties = if escaped_names.get() {
+        "\tescaped-names\n"
+    } else {
+        ""
+    };
+    format!(
+        "tiny\t2\t0\t{}\n{}{}",
+        header.join("\t"),
+        properties,
+        result
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TINY: &str = "tiny\t2\t0\tofficial\tintermediary\tnamed
+\tsorted-by\tofficial
+c\ta\tnet/minecraft/class_1\tnet/minecraft/block/Block
+\tc\tA block
+\tf\tI\tb\tfield_1\tluminance
+\tm\t(La;)V\tc\tmethod_1\tonSteppedOn
+\t\tc\tCalled when an entity steps on the block
+\t\tp\t1\t\t\tblock
+\t\t\tc\tThe block
+\t\tv\t2\t0\t\t\t\tlocal
+\t\t\tc\tSkipped with the variable
+\t\tx\tunknown section
+\t\t\tx\tchild of the unknown section
+c\tb\tnet/minecraft/class_2\tnet/minecraft/util/math/BlockPos
+";
+
+    fn parse(content: &str) -> Result<Mappings, MappingsError> {
+        let mut mappings = Mappings::new();
+        parse_tiny(content, &mut mappings)?;
+        Ok(mappings)
+    }
+
+    #[test]
+    fn round_trip() {
+        let written = write_tiny(&parse(TINY).unwrap());
+        assert_eq!(
+            written,
+            "tiny\t2\t0\tofficial\tintermediary\tnamed
+c\ta\tnet/minecraft/class_1\tnet/minecraft/block/Block
+\tc\tA block
+\tf\tI\tb\tfield_1\tluminance
+\tm\t(La;)V\tc\tmethod_1\tonSteppedOn
+\t\tc\tCalled when an entity steps on the block
+\t\tp\t1\t\t\tblock
+\t\t\tc\tThe block
+c\tb\tnet/minecraft/class_2\tnet/minecraft/util/math/BlockPos
+"
+        );
+        assert_eq!(write_tiny(&parse(&written).unwrap()), written);
+    }
+
+    #[test]
+    fn escaped_names() {
+        let mut mappings = parse(TINY).unwrap();
+        let class = mappings
+            .mapped_map
+            .get_mut("net/minecraft/block/Block")
+            .unwrap();
+        let method = class.methods.values_mut().next().unwrap();
+        method.args[0].name = "odd\tname\\".to_string();
+        let written = write_tiny(&mappings);
+        assert!(written.starts_with("tiny\t2\t0\tofficial\tintermediary\tnamed\n\tescaped-names\n"));
+        assert!(written.contains("\t\tp\t1\t\t\todd\\tname\\\\\n"));
+        assert_eq!(write_tiny(&parse(&written).unwrap()), written);
+    }
+
+    #[test]
+    fn malformed_lines() {
+        let error = |content: &str| match parse(content) {
+            Err(MappingsError::BadLine { line, .. }) => line,
+            result => panic!("{:?} parsed as {:?}", content, result.map(|_| ())),
+        };
+        assert_eq!(error("tiny\t1\tofficial"), "tiny\t1\tofficial");
+        assert_eq!(
+            error("tiny\t2\t0\tofficial\tnamed"),
+            "tiny\t2\t0\tofficial\tnamed"
+        );
+        let header = "tiny\t2\t0\tofficial\tintermediary\n";
+        assert_eq!(error(&format!("{}c\ta\tb\n\t\tc\td", header)), "\t\tc\td");
+        assert_eq!(
+            error(&format!("{}c\ta\tb\n\tm\t(I\tc\td", header)),
+            "\tm\t(I\tc\td"
+        );
+        assert_eq!(
+            error(&format!("{}c\ta\tb\n\tm\t()V\tc\td\n\t\tp\tx\te", header)),
+            "\t\tp\tx\te"
+        );
+    }
+
+    #[test]
+    fn multi_line_comments() {
+        let mappings = parse(
+            "tiny\t2\t0\tofficial\tintermediary\tnamed
+c\ta\tnet/minecraft/class_1\tnet/minecraft/block/Block
+\tc\tA block
+\tc\tplaced in the world
+\tm\t()V\tc\tmethod_1\ttick
+\t\tc\tFirst line
+\t\tc\t
+\t\tc\tThird line
+\t\tp\t1\t\t\tblock
+\t\t\tc\tThe
+\t\t\tc\tblock
+",
+        )
+        .unwrap();
+        let class = &mappings.mapped_map["net/minecraft/block/Block"];
+        assert_eq!(class.comments, "A block\nplaced in the world");
+        let method = class.methods.values().next().unwrap();
+        assert_eq!(method.comments, "First line\n\nThird line");
+        assert_eq!(method.args[0].comment, "The\nblock");
+        let written = write_tiny(&mappings);
+        assert!(written.contains("\tc\tA block\\nplaced in the world\n"));
+        assert_eq!(write_tiny(&parse(&written).unwrap()), written);
+    }
+}
@@ -0,0 +1,122 @@
+use std::{collections::HashMap, env, fs, sync::LazyLock};
+
+use proc_macro2::TokenStream;
+
+/// Java classes outside of the mappings which have a Rust counterpart in generated bindings
+pub static TYPE_MAPPINGS: LazyLock<TypeMappings> = LazyLock::new(TypeMappings::load);
+
+/// Rust counterpart of a java class. Returned values are converted with `FromJava`, parameters
+/// with `ToJava`
+#[derive(Debug, Clone)]
+pub struct TypeMapping {
+    /// Rust type of returned values and fields, `'a` being the lifetime of the wrapper and `$0`,
+    /// `$1`... the type arguments of generic classes
+    pub rust: String,
+    /// Rust type of parameters, `None` if values can't be sent to java
+    pub param: Option<String>,
+}
+
+impl TypeMapping {
+    /// Rust type with the Rust types of the type arguments, missing ones being `AnyObject`
+    pub fn rust_tokens(&self, args: &[TokenStream]) -> TokenStream {
+        let mut rust = String::new();
+        let mut letters = self.rust.chars().peekable();
+        while let Some(letter) = letters.next() {
+            if letter != '$' {
+                rust.push(letter);
+                continue;
+            }
+            let mut index = String::new();
+            while let Some(digit) = letters.next_if(|c| c.is_ascii_digit()) {
+                index.push(digit);
+            }
+            match index.parse::<usize>().ok().and_then(|i| args.get(i)) {
+                Some(arg) => rust += &arg.to_string(),
+                None => rust += "crate::java::AnyObject<'a>",
+            }
+        }
+        rust.parse()
+            .unwrap_or_else(|e| panic!("Invalid rust type {}: {}", self.rust, e))
+    }
+
+    pub fn param_tokens(&self) -> Option<TokenStream> {
+        self.param.as_ref().map(|param| {
+            param
+                .parse()
+                .unwrap_or_else(|e| panic!("Invalid rust type {}: {}", param, e))
+        })
+    }
+}
+
+/// Java classes, by internal name, to their Rust counterpart
+#[derive(Debug, Clone)]
+pub struct TypeMappings(HashMap<String, TypeMapping>);
+
+impl Default for TypeMappings {
+    fn default() -> Self {
+        let mut mappings = TypeMappings(HashMap::new());
+        mappings.insert("java/lang/String", "String", Some("&str"));
+        mappings.insert(
+            "java/lang/Object",
+            "crate::java::AnyObject<'a>",
+            Some("&crate::java::AnyObject<'_>"),
+        );
+        mappings.insert("java/util/Optional", "Option<$0>", None);
+        mappings.insert(
+            "java/util/UUID",
+            "crate::java::Uuid",
+            Some("crate::java::Uuid"),
+        );
+        mappings.insert("java/util/List", "crate::java::JavaList<'a, $0>", None);
+        for iterable in [
+            "java/lang/Iterable",
+            "java/util/Collection",
+            "java/util/Set",
+        ] {
+            mappings.insert(iterable, "crate::java::JavaIterable<'a, $0>", None);
+        }
+        mappings
+    }
+}
+
+impl TypeMappings {
+    /// Default mappings, completed by the file `RUSTCRAFT_TYPE_MAPPINGS` if set
+    pub fn load() -> Self {
+        let mut mappings = Self::default();
+        if let Ok(path) = env::var("RUSTCRAFT_TYPE_MAPPINGS") {
+            let content = fs::read_to_string(&path)
+                .unwrap_or_else(|e| panic!("Can't read type mappings {}: {}", path, e));
+            mappings.parse(&content);
+        }
+        mappings
+    }
+
+    /// Add entries written one per line as `java/class/Name<TAB>RustType[<TAB>ParamType]`, lines
+    /// starting with `#` being comments. Existing entries are replaced
+    pub fn parse(&mut self, content: &str) {
+        for line in content.lines() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut columns = line.split('\t').map(|column| column.trim());
+            match (columns.next(), columns.next()) {
+                (Some(java), Some(rust)) => self.insert(java, rust, columns.next()),
+                _ => panic!("Invalid type mapping '{}'", line),
+            }
+        }
+    }
+
+    pub fn insert(&mut self, java: &str, rust: &str, param: Option<&str>) {
+        self.0.insert(
+            java.to_string(),
+            TypeMapping {
+                rust: rust.to_string(),
+                param: param.map(|param| param.to_string()),
+            },
+        );
+    }
+
+    pub fn get(&self, java: &str) -> Option<&TypeMapping> {
+        self.0.get(java)
+    }
+}
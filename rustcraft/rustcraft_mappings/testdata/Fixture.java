@@ -0,0 +1,16 @@
+// Class files read by the tests of `classfile.rs`, compiled with
+// `javac --release 17 -d out Fixture.java org/jetbrains/annotations/Nullable.java`
+package net.minecraft;
+
+import org.jetbrains.annotations.Nullable;
+
+public final class Fixture<T extends Comparable<T>> {
+    public static final int CONSTANT = 1;
+    protected T value;
+
+    public static void run(long time, @Nullable String name) {}
+
+    public class Inner {
+        public Inner(@Nullable String name, int count) {}
+    }
+}
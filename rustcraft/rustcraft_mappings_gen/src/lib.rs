@@ -2,6 +2,7 @@ use jni::objects::JObject;
 use jni::sys::jint;
 use jni::JNIEnv;
 use jni::JNIVersion;
+use rustcraft_mappings::{cache::CacheKey, Namespace};
 
 #[no_mangle]
 extern "system" fn JNI_OnLoad(mut _env: JNIEnv) -> jint {
@@ -15,12 +16,16 @@ extern "system" fn Java_fr_supersurviveur_mappingsmod_Mappingsmod_run(
 ) {
     println!("Starting mappings completion...");
 
-    let mut mappings = rustcraft_mappings::parse_mappings();
+    // The game isn't run with the environment of the build, so its key would differ from the one
+    // of the cache read by the proc macros
+    let key = CacheKey::recorded().unwrap_or_else(CacheKey::current);
+    let mut mappings = rustcraft_mappings::read_mappings(key);
 
     let java_utils = env
         .find_class("fr/supersurviveur/mappingsmod/ASMUtils")
         .unwrap();
 
+    // Classes keep `modifiers_known` from the game jar, as their own modifiers aren't read here
     for c in mappings.mapped_map.values_mut() {
         for method in c.methods.values_mut() {
             let modifiers = env
@@ -29,25 +34,41 @@ extern "system" fn Java_fr_supersurviveur_mappingsmod_Mappingsmod_run(
                     "getModifiers",
                     "(Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;)C",
                     &[
-                        (&env.new_string(&c.mapped_name).unwrap()).into(),
-                        (&env.new_string(&method.mapped_name).unwrap()).into(),
-                        (&env.new_string(&method.mapped_signature).unwrap()).into(),
+                        (&env.new_string(c.names.name(Namespace::NAMED)).unwrap()).into(),
+                        (&env.new_string(method.names.name(Namespace::NAMED)).unwrap()).into(),
+                        (&env
+                            .new_string(method.descriptors.name(Namespace::NAMED))
+                            .unwrap())
+                            .into(),
                     ],
                 )
                 .inspect_err(|_| env.exception_describe().unwrap())
                 .unwrap()
                 .c()
                 .unwrap();
-            for (i, arg) in method.args.iter_mut().enumerate() {
+            method.modifiers = modifiers as u8;
+            let indexes: Vec<Option<usize>> = method
+                .args
+                .iter()
+                .map(|arg| method.parameter_index(arg.position))
+                .collect();
+            for (arg, i) in method.args.iter_mut().zip(indexes) {
+                // Arguments are numbered by local variable slot, not by parameter
+                let Some(i) = i else {
+                    continue;
+                };
                 let modifiers = env
                     .call_static_method(
                         &java_utils,
                         "getModifiers",
                         "(Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;I)C",
                         &[
-                            (&env.new_string(&c.mapped_name).unwrap()).into(),
-                            (&env.new_string(&method.mapped_name).unwrap()).into(),
-                            (&env.new_string(&method.mapped_signature).unwrap()).into(),
+                            (&env.new_string(c.names.name(Namespace::NAMED)).unwrap()).into(),
+                            (&env.new_string(method.names.name(Namespace::NAMED)).unwrap()).into(),
+                            (&env
+                                .new_string(method.descriptors.name(Namespace::NAMED))
+                                .unwrap())
+                                .into(),
                             (i as jint).into(),
                         ],
                     )
@@ -57,7 +78,6 @@ extern "system" fn Java_fr_supersurviveur_mappingsmod_Mappingsmod_run(
                     .unwrap();
                 arg.modifiers = modifiers as u8;
             }
-            method.modifiers = modifiers as u8;
         }
         for field in c.fields.values_mut() {
             let modifiers = env
@@ -66,8 +86,8 @@ extern "system" fn Java_fr_supersurviveur_mappingsmod_Mappingsmod_run(
                     "getModifiers",
                     "(Ljava/lang/String;Ljava/lang/String;)C",
                     &[
-                        (&env.new_string(&c.mapped_name).unwrap()).into(),
-                        (&env.new_string(&field.mapped_name).unwrap()).into(),
+                        (&env.new_string(c.names.name(Namespace::NAMED)).unwrap()).into(),
+                        (&env.new_string(field.names.name(Namespace::NAMED)).unwrap()).into(),
                     ],
                 )
                 .inspect_err(|_| env.exception_describe().unwrap())
@@ -78,7 +98,7 @@ extern "system" fn Java_fr_supersurviveur_mappingsmod_Mappingsmod_run(
         }
     }
 
-    rustcraft_mappings::set_mappings(&mappings);
+    rustcraft_mappings::write_mappings(key, &mappings);
 
     println!("Mappings completion complete !");
 }
@@ -4,9 +4,11 @@ use std::rc::Rc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 use crate::block::Block;
-use jni::descriptors::Desc;
-use jni::objects::{JClass, JObject, JString, JValue, JValueGen};
-use jni::signature::TypeSignature;
+use crate::cache::{CachedField, CachedMethod, CachedStaticField, CachedStaticMethod};
+use jni::objects::{
+    JBooleanArray, JClass, JObject, JObjectArray, JPrimitiveArray, JString, JValue, JValueGen,
+};
+use jni::signature::{JavaType, ReturnType};
 use jni::sys::{jlong, jvalue};
 use jni::{JNIEnv, NativeMethod};
 
@@ -52,35 +54,6 @@ impl<'local> BaseApi<'local> {
                 signature.0, signature.1
             ))
     }
-    pub(crate) fn get_field_class<'a>(
-        &mut self,
-        class: Option<&str>,
-        object: Option<&'a JObject<'a>>,
-        signature: (&str, &str),
-    ) -> JValueGen<JObject<'a>>
-    where
-        'local: 'a,
-    {
-        let class = class
-            .map(|c| Desc::<JClass>::lookup(c, &mut self.current_env).unwrap())
-            .unwrap_or(
-                self.current_env.auto_local(
-                    self.current_env
-                        .get_object_class(object.unwrap_or(&self.current_obj))
-                        .unwrap(),
-                ),
-            );
-        let ret = TypeSignature::from_str(signature.1).unwrap().ret;
-
-        self.current_env
-            .get_field_unchecked(
-                object.unwrap_or(&self.current_obj),
-                (class, signature.0, signature.1),
-                ret,
-            )
-            .inspect_err(|_| self.current_env.exception_describe().unwrap())
-            .unwrap()
-    }
     pub(crate) fn call_method<'a>(
         &mut self,
         object: Option<&'a JObject<'a>>,
@@ -100,38 +73,6 @@ impl<'local> BaseApi<'local> {
             .inspect_err(|_| self.current_env.exception_describe().unwrap())
             .unwrap()
     }
-    pub(crate) fn call_method_class<'a>(
-        &mut self,
-        class: Option<&str>,
-        object: Option<&'a JObject<'a>>,
-        signature: (&str, &str),
-        args: &[JValue],
-    ) -> JValueGen<JObject<'a>>
-    where
-        'local: 'a,
-    {
-        let class = class
-            .map(|c| Desc::<JClass>::lookup(c, &mut self.current_env).unwrap())
-            .unwrap_or(
-                self.current_env.auto_local(
-                    self.current_env
-                        .get_object_class(object.unwrap_or(&self.current_obj))
-                        .unwrap(),
-                ),
-            );
-        let ret = TypeSignature::from_str(signature.1).unwrap().ret;
-        let args: Vec<jvalue> = args.iter().map(|v| v.as_jni()).collect();
-        unsafe {
-            self.current_env.call_method_unchecked(
-                object.unwrap_or(&self.current_obj),
-                (class, signature.0, signature.1),
-                ret,
-                &args,
-            )
-        }
-        .inspect_err(|_| self.current_env.exception_describe().unwrap())
-        .unwrap()
-    }
     pub(crate) fn call_method_object<'a>(
         &mut self,
         object: Option<&'a JObject<'a>>,
@@ -172,19 +113,6 @@ impl<'a> ModApi<'a> {
     {
         self.api.borrow_mut().get_field(java_class, signature)
     }
-    pub(crate) fn get_field_class<'local>(
-        &self,
-        class: Option<&str>,
-        object: Option<&'local JObject<'local>>,
-        signature: (&str, &str),
-    ) -> JValueGen<JObject<'local>>
-    where
-        'a: 'local,
-    {
-        self.api
-            .borrow_mut()
-            .get_field_class(class, object, signature)
-    }
     pub(crate) fn call_method<'local>(
         &self,
         object: Option<&'local JObject<'local>>,
@@ -198,20 +126,6 @@ impl<'a> ModApi<'a> {
             .borrow_mut()
             .call_method(object, signature, args)
     }
-    pub(crate) fn call_method_class<'local>(
-        &self,
-        class: Option<&str>,
-        object: Option<&'local JObject<'local>>,
-        signature: (&str, &str),
-        args: &[JValue],
-    ) -> JValueGen<JObject<'local>>
-    where
-        'a: 'local,
-    {
-        self.api
-            .borrow_mut()
-            .call_method_class(class, object, signature, args)
-    }
     pub(crate) fn get_block_manager(&self) -> JObject<'a> {
         (*self.api).borrow_mut().get_block_manager()
     }
@@ -226,6 +140,155 @@ impl<'a> ModApi<'a> {
             .expect("Error while creating a java string")
     }
 
+    pub(crate) fn rust_string(&self, string: &JObject) -> String {
+        (*self.api)
+            .borrow_mut()
+            .current_env
+            .get_string(string.into())
+            .expect("Error while reading a java string")
+            .into()
+    }
+
+    /// Copy a slice to a new java array of primitives
+    pub(crate) fn new_primitive_array<T: JavaPrimitive>(&self, values: &[T]) -> JObject<'a> {
+        T::new_array(&mut (*self.api).borrow_mut().current_env, values)
+    }
+
+    /// Copy a java array of primitives
+    pub(crate) fn primitive_array_to_vec<T: JavaPrimitive>(&self, array: JObject) -> Vec<T> {
+        T::from_array(&mut (*self.api).borrow_mut().current_env, array)
+    }
+
+    pub(crate) fn get_array_length(&self, array: &JObjectArray) -> usize {
+        (*self.api)
+            .borrow_mut()
+            .current_env
+            .get_array_length(array)
+            .unwrap() as usize
+    }
+
+    pub(crate) fn get_array_element(&self, array: &JObjectArray, index: usize) -> JObject<'a> {
+        let mut api = (*self.api).borrow_mut();
+        api.current_env
+            .get_object_array_element(array, index.try_into().unwrap())
+            .inspect_err(|_| api.current_env.exception_describe().unwrap())
+            .unwrap()
+    }
+
+    /// Create a java array of `class` objects
+    pub(crate) fn new_object_array(&self, class: &str, values: &[&JObject]) -> JObject<'a> {
+        let mut api = (*self.api).borrow_mut();
+        let array = api
+            .current_env
+            .new_object_array(values.len().try_into().unwrap(), class, JObject::null())
+            .inspect_err(|_| api.current_env.exception_describe().unwrap())
+            .unwrap();
+        for (i, value) in values.iter().enumerate() {
+            api.current_env
+                .set_object_array_element(&array, i.try_into().unwrap(), value)
+                .unwrap();
+        }
+        array.into()
+    }
+
+    /// Call a method of `object`, resolved once
+    pub(crate) fn call_cached_method(
+        &self,
+        object: &JObject,
+        method: &CachedMethod,
+        args: &[JValue],
+    ) -> JValueGen<JObject<'a>> {
+        let mut api = (*self.api).borrow_mut();
+        let env = &mut api.current_env;
+        let id = method.id(env);
+        let args: Vec<jvalue> = args.iter().map(|v| v.as_jni()).collect();
+        unsafe { env.call_method_unchecked(object, id, method.return_type(), &args) }
+            .inspect_err(|_| env.exception_describe().unwrap())
+            .unwrap()
+    }
+
+    /// Call a static method, resolved once
+    pub(crate) fn call_cached_static_method(
+        &self,
+        method: &CachedStaticMethod,
+        args: &[JValue],
+    ) -> JValueGen<JObject<'a>> {
+        let mut api = (*self.api).borrow_mut();
+        let env = &mut api.current_env;
+        let id = method.id(env);
+        let class = method.class(env);
+        let args: Vec<jvalue> = args.iter().map(|v| v.as_jni()).collect();
+        unsafe { env.call_static_method_unchecked(class, id, method.return_type(), &args) }
+            .inspect_err(|_| env.exception_describe().unwrap())
+            .unwrap()
+    }
+
+    /// Create a java object with a constructor resolved once
+    pub(crate) fn new_cached_object(
+        &self,
+        constructor: &CachedMethod,
+        args: &[JValue],
+    ) -> JObject<'a> {
+        let mut api = (*self.api).borrow_mut();
+        let env = &mut api.current_env;
+        let id = constructor.id(env);
+        let class = constructor.class(env);
+        let args: Vec<jvalue> = args.iter().map(|v| v.as_jni()).collect();
+        unsafe { env.new_object_unchecked(class, id, &args) }
+            .inspect_err(|_| env.exception_describe().unwrap())
+            .unwrap()
+    }
+
+    pub(crate) fn get_cached_field(
+        &self,
+        object: &JObject,
+        field: &CachedField,
+    ) -> JValueGen<JObject<'a>> {
+        let mut api = (*self.api).borrow_mut();
+        let env = &mut api.current_env;
+        let id = field.id(env);
+        env.get_field_unchecked(object, id, field.return_type())
+            .inspect_err(|_| env.exception_describe().unwrap())
+            .unwrap()
+    }
+
+    pub(crate) fn set_cached_field(&self, object: &JObject, field: &CachedField, value: JValue) {
+        let mut api = (*self.api).borrow_mut();
+        let env = &mut api.current_env;
+        let id = field.id(env);
+        env.set_field_unchecked(object, id, value)
+            .inspect_err(|_| env.exception_describe().unwrap())
+            .unwrap()
+    }
+
+    pub(crate) fn get_cached_static_field(
+        &self,
+        field: &CachedStaticField,
+    ) -> JValueGen<JObject<'a>> {
+        let mut api = (*self.api).borrow_mut();
+        let env = &mut api.current_env;
+        let id = field.id(env);
+        let class = field.class(env);
+        // Only the kind of the type is used by JNI
+        let ty = match field.return_type() {
+            ReturnType::Primitive(primitive) => JavaType::Primitive(primitive),
+            ReturnType::Object | ReturnType::Array => JavaType::Object(String::new()),
+        };
+        env.get_static_field_unchecked(class, id, ty)
+            .inspect_err(|_| env.exception_describe().unwrap())
+            .unwrap()
+    }
+
+    pub(crate) fn set_cached_static_field(&self, field: &CachedStaticField, value: JValue) {
+        let mut api = (*self.api).borrow_mut();
+        let env = &mut api.current_env;
+        let id = field.id(env);
+        let class = field.class(env);
+        env.set_static_field(class, id, value)
+            .inspect_err(|_| env.exception_describe().unwrap())
+            .unwrap()
+    }
+
     pub fn get_class(&self, class: &str) -> JClass {
         (*self.api)
             .borrow_mut()
@@ -248,6 +311,33 @@ impl<'a> ModApi<'a> {
                 signature.0, signature.1
             ))
     }
+    /// Whether `object` is an instance of `class` or of one of its subclasses
+    pub(crate) fn is_instance_of(&self, object: &JObject, class: &str) -> bool {
+        let mut api = (*self.api).borrow_mut();
+        api.current_env
+            .is_instance_of(object, class)
+            .inspect_err(|_| api.current_env.exception_describe().unwrap())
+            .unwrap()
+    }
+
+    /// Free a local reference before the native method returns, for objects created in loops
+    pub(crate) fn delete_local_ref(&self, object: JObject) {
+        (*self.api)
+            .borrow_mut()
+            .current_env
+            .delete_local_ref(object)
+            .unwrap()
+    }
+    pub fn set_static_field(&self, class: &JClass, signature: (&str, &str), value: JValue) {
+        (*self.api)
+            .borrow_mut()
+            .current_env
+            .set_static_field(class, (class, signature.0, signature.1), value)
+            .expect(&format!(
+                "Couldn't set field {}: {}",
+                signature.0, signature.1
+            ))
+    }
     pub(crate) fn call_static_method(
         &self,
         class: &JClass,
@@ -374,7 +464,7 @@ impl<'a> ModApi<'a> {
             &[(&self.java_string(s)).into()],
         );
     }
-    pub fn new_local_ref<O: AsRef<JObject<'a>>>(&self, value: &O) -> JObject<'a> {
+    pub fn new_local_ref<'other, O: AsRef<JObject<'other>>>(&self, value: &O) -> JObject<'a> {
         (*self.api)
             .borrow_mut()
             .current_env
@@ -382,3 +472,56 @@ impl<'a> ModApi<'a> {
             .unwrap()
     }
 }
+
+/// Primitive types which can be sent to and read from java arrays
+pub trait JavaPrimitive: Sized {
+    fn new_array<'local>(env: &mut JNIEnv<'local>, values: &[Self]) -> JObject<'local>;
+    fn from_array(env: &mut JNIEnv, array: JObject) -> Vec<Self>;
+}
+
+macro_rules! impl_java_primitive {
+    ($($t:ty => $new:ident, $set:ident, $get:ident;)*) => {$(
+        impl JavaPrimitive for $t {
+            fn new_array<'local>(env: &mut JNIEnv<'local>, values: &[Self]) -> JObject<'local> {
+                let array = env.$new(values.len().try_into().unwrap()).unwrap();
+                env.$set(&array, 0, values).unwrap();
+                array.into()
+            }
+            fn from_array(env: &mut JNIEnv, array: JObject) -> Vec<Self> {
+                let array = JPrimitiveArray::<$t>::from(array);
+                let len = env.get_array_length(&array).unwrap();
+                let mut values = vec![Self::default(); len as usize];
+                env.$get(&array, 0, &mut values).unwrap();
+                values
+            }
+        }
+    )*};
+}
+impl_java_primitive! {
+    i8 => new_byte_array, set_byte_array_region, get_byte_array_region;
+    u16 => new_char_array, set_char_array_region, get_char_array_region;
+    i16 => new_short_array, set_short_array_region, get_short_array_region;
+    i32 => new_int_array, set_int_array_region, get_int_array_region;
+    i64 => new_long_array, set_long_array_region, get_long_array_region;
+    f32 => new_float_array, set_float_array_region, get_float_array_region;
+    f64 => new_double_array, set_double_array_region, get_double_array_region;
+}
+
+impl JavaPrimitive for bool {
+    fn new_array<'local>(env: &mut JNIEnv<'local>, values: &[Self]) -> JObject<'local> {
+        let values: Vec<u8> = values.iter().map(|v| *v as u8).collect();
+        let array = env
+            .new_boolean_array(values.len().try_into().unwrap())
+            .unwrap();
+        env.set_boolean_array_region(&array, 0, &values).unwrap();
+        array.into()
+    }
+    fn from_array(env: &mut JNIEnv, array: JObject) -> Vec<Self> {
+        let array = JBooleanArray::from(array);
+        let len = env.get_array_length(&array).unwrap();
+        let mut values = vec![0; len as usize];
+        env.get_boolean_array_region(&array, 0, &mut values)
+            .unwrap();
+        values.into_iter().map(|v| v != 0).collect()
+    }
+}
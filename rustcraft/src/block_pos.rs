@@ -1,7 +1,8 @@
 use jni::objects::JObject;
-use rustcraft_codegen::mappings_with_sig;
+use rustcraft_codegen::{mappings_class, mappings_with_sig};
 
 use crate::api::ModApi;
+use crate::cache::CachedMethod;
 
 #[derive(Debug)]
 pub struct BlockPos<'a> {
@@ -14,32 +15,32 @@ impl<'a> BlockPos<'a> {
         BlockPos { api, block_pos }
     }
     pub fn get_x(&self) -> i32 {
+        static GET_X: CachedMethod = CachedMethod::with_sig(
+            mappings_class!("net/minecraft/util/math/BlockPos"),
+            mappings_with_sig!("net/minecraft/util/math/Vec3i", "getX"),
+        );
         self.api
-            .call_method(
-                Some(&self.block_pos),
-                mappings_with_sig!("net/minecraft/util/math/Vec3i", "getX"),
-                &[],
-            )
+            .call_cached_method(&self.block_pos, &GET_X, &[])
             .i()
             .unwrap()
     }
     pub fn get_y(&self) -> i32 {
+        static GET_Y: CachedMethod = CachedMethod::with_sig(
+            mappings_class!("net/minecraft/util/math/BlockPos"),
+            mappings_with_sig!("net/minecraft/util/math/Vec3i", "getY"),
+        );
         self.api
-            .call_method(
-                Some(&self.block_pos),
-                mappings_with_sig!("net/minecraft/util/math/Vec3i", "getY"),
-                &[],
-            )
+            .call_cached_method(&self.block_pos, &GET_Y, &[])
             .i()
             .unwrap()
     }
     pub fn get_z(&self) -> i32 {
+        static GET_Z: CachedMethod = CachedMethod::with_sig(
+            mappings_class!("net/minecraft/util/math/BlockPos"),
+            mappings_with_sig!("net/minecraft/util/math/Vec3i", "getZ"),
+        );
         self.api
-            .call_method(
-                Some(&self.block_pos),
-                mappings_with_sig!("net/minecraft/util/math/Vec3i", "getZ"),
-                &[],
-            )
+            .call_cached_method(&self.block_pos, &GET_Z, &[])
             .i()
             .unwrap()
     }
@@ -0,0 +1,139 @@
+//! Classes and member IDs used by the bindings, looked up on their first use only. Generated
+//! methods keep them in statics, like
+//!
+//! ```ignore
+//! static METHOD: CachedMethod = CachedMethod::new("net/minecraft/class_2338", "method_10263", "()I");
+//! api.call_cached_method(&self.inner, &METHOD, &[]).i().unwrap()
+//! ```
+use std::sync::OnceLock;
+
+use jni::errors::Result;
+use jni::objects::{GlobalRef, JClass, JFieldID, JMethodID, JStaticFieldID, JStaticMethodID};
+use jni::signature::{Primitive, ReturnType};
+use jni::JNIEnv;
+
+/// Class found by name once, kept alive by a global reference
+#[derive(Debug)]
+pub struct CachedClass {
+    name: &'static str,
+    class: OnceLock<GlobalRef>,
+}
+
+impl CachedClass {
+    pub const fn new(name: &'static str) -> Self {
+        CachedClass {
+            name,
+            class: OnceLock::new(),
+        }
+    }
+
+    pub(crate) fn get(&self, env: &mut JNIEnv) -> &JClass<'static> {
+        let class = self.class.get_or_init(|| {
+            let class = env
+                .find_class(self.name)
+                .inspect_err(|_| env.exception_describe().unwrap())
+                .expect(&format!("Couldn't find class {}", self.name));
+            env.new_global_ref(class).unwrap()
+        });
+        <&JClass>::from(class.as_obj())
+    }
+}
+
+/// JNI ID of a method or a field
+pub trait MemberId: Copy + Send + Sync {
+    fn lookup(env: &mut JNIEnv, class: &JClass, name: &str, signature: &str) -> Result<Self>;
+}
+
+impl MemberId for JMethodID {
+    fn lookup(env: &mut JNIEnv, class: &JClass, name: &str, signature: &str) -> Result<Self> {
+        env.get_method_id(class, name, signature)
+    }
+}
+
+impl MemberId for JStaticMethodID {
+    fn lookup(env: &mut JNIEnv, class: &JClass, name: &str, signature: &str) -> Result<Self> {
+        env.get_static_method_id(class, name, signature)
+    }
+}
+
+impl MemberId for JFieldID {
+    fn lookup(env: &mut JNIEnv, class: &JClass, name: &str, signature: &str) -> Result<Self> {
+        env.get_field_id(class, name, signature)
+    }
+}
+
+impl MemberId for JStaticFieldID {
+    fn lookup(env: &mut JNIEnv, class: &JClass, name: &str, signature: &str) -> Result<Self> {
+        env.get_static_field_id(class, name, signature)
+    }
+}
+
+/// Method or field of a class, names being the ones of the `TARGET` namespace. Its class and its
+/// ID are looked up on the first call
+pub struct CachedMember<Id> {
+    class: CachedClass,
+    name: &'static str,
+    signature: &'static str,
+    id: OnceLock<Id>,
+}
+
+pub type CachedMethod = CachedMember<JMethodID>;
+pub type CachedStaticMethod = CachedMember<JStaticMethodID>;
+pub type CachedField = CachedMember<JFieldID>;
+pub type CachedStaticField = CachedMember<JStaticFieldID>;
+
+impl<Id: MemberId> CachedMember<Id> {
+    pub const fn new(class: &'static str, name: &'static str, signature: &'static str) -> Self {
+        CachedMember {
+            class: CachedClass::new(class),
+            name,
+            signature,
+            id: OnceLock::new(),
+        }
+    }
+
+    /// Member of `class` given as returned by `mappings_with_sig!`
+    pub const fn with_sig(
+        class: &'static str,
+        (name, signature): (&'static str, &'static str),
+    ) -> Self {
+        Self::new(class, name, signature)
+    }
+
+    pub(crate) fn class(&self, env: &mut JNIEnv) -> &JClass<'static> {
+        self.class.get(env)
+    }
+
+    pub(crate) fn id(&self, env: &mut JNIEnv) -> Id {
+        *self.id.get_or_init(|| {
+            let class = self.class.get(env);
+            Id::lookup(env, class, self.name, self.signature)
+                .inspect_err(|_| env.exception_describe().unwrap())
+                .expect(&format!(
+                    "Couldn't find {}.{}: {}",
+                    self.class.name, self.name, self.signature
+                ))
+        })
+    }
+
+    /// Type of the field or returned by the method, read from the first letter of its descriptor
+    pub(crate) fn return_type(&self) -> ReturnType {
+        let descriptor = self
+            .signature
+            .rsplit_once(')')
+            .map_or(self.signature, |(_, ret)| ret);
+        match descriptor.as_bytes().first() {
+            Some(b'Z') => ReturnType::Primitive(Primitive::Boolean),
+            Some(b'B') => ReturnType::Primitive(Primitive::Byte),
+            Some(b'C') => ReturnType::Primitive(Primitive::Char),
+            Some(b'S') => ReturnType::Primitive(Primitive::Short),
+            Some(b'I') => ReturnType::Primitive(Primitive::Int),
+            Some(b'J') => ReturnType::Primitive(Primitive::Long),
+            Some(b'F') => ReturnType::Primitive(Primitive::Float),
+            Some(b'D') => ReturnType::Primitive(Primitive::Double),
+            Some(b'V') => ReturnType::Primitive(Primitive::Void),
+            Some(b'[') => ReturnType::Array,
+            _ => ReturnType::Object,
+        }
+    }
+}
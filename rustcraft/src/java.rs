@@ -0,0 +1,230 @@
+//! Rust counterparts of the java standard types used by generated bindings
+use std::marker::PhantomData;
+
+use jni::objects::{JObject, JValue};
+
+use crate::api::ModApi;
+use crate::cache::CachedMethod;
+use crate::object::{FromJava, ToJava};
+
+static OPTIONAL_IS_PRESENT: CachedMethod =
+    CachedMethod::new("java/util/Optional", "isPresent", "()Z");
+static OPTIONAL_GET: CachedMethod =
+    CachedMethod::new("java/util/Optional", "get", "()Ljava/lang/Object;");
+static UUID_NEW: CachedMethod = CachedMethod::new("java/util/UUID", "<init>", "(JJ)V");
+static UUID_MOST_SIGNIFICANT_BITS: CachedMethod =
+    CachedMethod::new("java/util/UUID", "getMostSignificantBits", "()J");
+static UUID_LEAST_SIGNIFICANT_BITS: CachedMethod =
+    CachedMethod::new("java/util/UUID", "getLeastSignificantBits", "()J");
+static ITERABLE_ITERATOR: CachedMethod =
+    CachedMethod::new("java/lang/Iterable", "iterator", "()Ljava/util/Iterator;");
+static ITERATOR_HAS_NEXT: CachedMethod = CachedMethod::new("java/util/Iterator", "hasNext", "()Z");
+static ITERATOR_NEXT: CachedMethod =
+    CachedMethod::new("java/util/Iterator", "next", "()Ljava/lang/Object;");
+static LIST_SIZE: CachedMethod = CachedMethod::new("java/util/List", "size", "()I");
+static LIST_GET: CachedMethod = CachedMethod::new("java/util/List", "get", "(I)Ljava/lang/Object;");
+
+/// Any java object, used when the class of a value isn't known
+#[derive(Debug)]
+pub struct AnyObject<'a> {
+    pub api: ModApi<'a>,
+    pub inner: JObject<'a>,
+}
+
+impl<'a> FromJava<'a> for AnyObject<'a> {
+    fn from_java(api: ModApi<'a>, object: JObject<'a>) -> Self {
+        AnyObject { api, inner: object }
+    }
+}
+
+impl ToJava for &AnyObject<'_> {
+    fn to_java<'local>(self, api: ModApi<'local>) -> JObject<'local> {
+        api.new_local_ref(&self.inner)
+    }
+}
+
+impl<'a> FromJava<'a> for String {
+    fn from_java(api: ModApi<'a>, object: JObject<'a>) -> Self {
+        api.rust_string(&object)
+    }
+}
+
+impl ToJava for &str {
+    fn to_java<'local>(self, api: ModApi<'local>) -> JObject<'local> {
+        api.java_string(self).into()
+    }
+}
+
+impl ToJava for &String {
+    fn to_java<'local>(self, api: ModApi<'local>) -> JObject<'local> {
+        api.java_string(self).into()
+    }
+}
+
+/// `java.util.Optional`
+impl<'a, T: FromJava<'a>> FromJava<'a> for Option<T> {
+    fn from_java(api: ModApi<'a>, object: JObject<'a>) -> Self {
+        let present = api
+            .call_cached_method(&object, &OPTIONAL_IS_PRESENT, &[])
+            .z()
+            .unwrap();
+        if !present {
+            return None;
+        }
+        let value = api
+            .call_cached_method(&object, &OPTIONAL_GET, &[])
+            .l()
+            .unwrap();
+        Some(T::from_java(api, value))
+    }
+}
+
+/// `java.util.UUID`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Uuid {
+    pub most_significant_bits: i64,
+    pub least_significant_bits: i64,
+}
+
+impl Uuid {
+    pub fn as_u128(&self) -> u128 {
+        ((self.most_significant_bits as u64 as u128) << 64)
+            | self.least_significant_bits as u64 as u128
+    }
+}
+
+impl<'a> FromJava<'a> for Uuid {
+    fn from_java(api: ModApi<'a>, object: JObject<'a>) -> Self {
+        let bits = |method| api.call_cached_method(&object, method, &[]).j().unwrap();
+        Uuid {
+            most_significant_bits: bits(&UUID_MOST_SIGNIFICANT_BITS),
+            least_significant_bits: bits(&UUID_LEAST_SIGNIFICANT_BITS),
+        }
+    }
+}
+
+impl ToJava for &Uuid {
+    fn to_java<'local>(self, api: ModApi<'local>) -> JObject<'local> {
+        (*self).to_java(api)
+    }
+}
+
+impl ToJava for Uuid {
+    fn to_java<'local>(self, api: ModApi<'local>) -> JObject<'local> {
+        api.new_cached_object(
+            &UUID_NEW,
+            &[
+                JValue::Long(self.most_significant_bits),
+                JValue::Long(self.least_significant_bits),
+            ],
+        )
+    }
+}
+
+/// Iterator over a `java.util.Iterator`
+pub struct JavaIterator<'a, T> {
+    api: ModApi<'a>,
+    inner: JObject<'a>,
+    element: PhantomData<T>,
+}
+
+impl<'a, T: FromJava<'a>> Iterator for JavaIterator<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let has_next = self
+            .api
+            .call_cached_method(&self.inner, &ITERATOR_HAS_NEXT, &[])
+            .z()
+            .unwrap();
+        if !has_next {
+            return None;
+        }
+        let value = self
+            .api
+            .call_cached_method(&self.inner, &ITERATOR_NEXT, &[])
+            .l()
+            .unwrap();
+        Some(T::from_java(self.api.clone(), value))
+    }
+}
+
+fn iterator<'a, T>(api: &ModApi<'a>, iterable: &JObject) -> JavaIterator<'a, T> {
+    let inner = api
+        .call_cached_method(iterable, &ITERABLE_ITERATOR, &[])
+        .l()
+        .unwrap();
+    JavaIterator {
+        api: api.clone(),
+        inner,
+        element: PhantomData,
+    }
+}
+
+/// `java.lang.Iterable` and collections without indexed access
+#[derive(Debug)]
+pub struct JavaIterable<'a, T> {
+    pub api: ModApi<'a>,
+    pub inner: JObject<'a>,
+    element: PhantomData<T>,
+}
+
+impl<'a, T: FromJava<'a>> JavaIterable<'a, T> {
+    pub fn iter(&self) -> JavaIterator<'a, T> {
+        iterator(&self.api, &self.inner)
+    }
+}
+
+impl<'a, T> FromJava<'a> for JavaIterable<'a, T> {
+    fn from_java(api: ModApi<'a>, object: JObject<'a>) -> Self {
+        JavaIterable {
+            api,
+            inner: object,
+            element: PhantomData,
+        }
+    }
+}
+
+/// `java.util.List`, elements are converted when they are accessed
+#[derive(Debug)]
+pub struct JavaList<'a, T> {
+    pub api: ModApi<'a>,
+    pub inner: JObject<'a>,
+    element: PhantomData<T>,
+}
+
+impl<'a, T: FromJava<'a>> JavaList<'a, T> {
+    pub fn len(&self) -> usize {
+        self.api
+            .call_cached_method(&self.inner, &LIST_SIZE, &[])
+            .i()
+            .unwrap() as usize
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    pub fn get(&self, index: usize) -> Option<T> {
+        if index >= self.len() {
+            return None;
+        }
+        let value = self
+            .api
+            .call_cached_method(&self.inner, &LIST_GET, &[JValue::Int(index as i32)])
+            .l()
+            .unwrap();
+        Some(T::from_java(self.api.clone(), value))
+    }
+    pub fn iter(&self) -> JavaIterator<'a, T> {
+        iterator(&self.api, &self.inner)
+    }
+}
+
+impl<'a, T> FromJava<'a> for JavaList<'a, T> {
+    fn from_java(api: ModApi<'a>, object: JObject<'a>) -> Self {
+        JavaList {
+            api,
+            inner: object,
+            element: PhantomData,
+        }
+    }
+}
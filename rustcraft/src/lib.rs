@@ -8,6 +8,8 @@ pub use rustcraft_codegen::register_block;
 
 pub mod api;
 pub mod block;
+pub mod cache;
+pub mod java;
 pub mod object;
 pub mod prelude;
 
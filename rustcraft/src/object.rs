@@ -1,6 +1,9 @@
-use jni::objects::JObject;
+use std::marker::PhantomData;
+
+use jni::objects::{JObject, JObjectArray};
 
 use super::ModApi;
+use crate::api::JavaPrimitive;
 
 pub trait RustObject {}
 
@@ -20,4 +23,73 @@ pub trait ToJava {
 pub trait JavaObject<'a> {
     fn get_inner(&self) -> &JObject<'_>;
     fn get_api(&self) -> ModApi<'a>;
+
+    /// Wrap this object in the wrapper of one of its subclasses, `None` if it isn't an instance of
+    /// `T`. Upcasts are done with `Into`, from a wrapper or from a reference to it
+    fn downcast<T: JavaClass<'a>>(&self) -> Option<T> {
+        let api = self.get_api();
+        if !api.is_instance_of(self.get_inner(), T::JAVA_NAME) {
+            return None;
+        }
+        let inner = api.new_local_ref(self.get_inner());
+        Some(T::from_java(api, inner))
+    }
+}
+
+/// Generated wrapper of a java class
+pub trait JavaClass<'a>: FromJava<'a> {
+    /// Name of the wrapped class in the `TARGET` namespace
+    const JAVA_NAME: &'static str;
+}
+
+/// Build a rust value from a java object returned by the game
+pub trait FromJava<'a> {
+    fn from_java(api: ModApi<'a>, object: JObject<'a>) -> Self;
+}
+
+impl<'a, T: JavaPrimitive> FromJava<'a> for Vec<T> {
+    fn from_java(api: ModApi<'a>, object: JObject<'a>) -> Self {
+        api.primitive_array_to_vec(object)
+    }
+}
+
+/// Java array of objects, elements are converted when they are accessed
+#[derive(Debug)]
+pub struct JavaArray<'a, T> {
+    api: ModApi<'a>,
+    inner: JObjectArray<'a>,
+    len: usize,
+    element: PhantomData<T>,
+}
+
+impl<'a, T: FromJava<'a>> JavaArray<'a, T> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    pub fn get(&self, index: usize) -> Option<T> {
+        if index >= self.len {
+            return None;
+        }
+        let element = self.api.get_array_element(&self.inner, index);
+        Some(T::from_java(self.api.clone(), element))
+    }
+    pub fn iter(&self) -> impl Iterator<Item = T> + use<'_, 'a, T> {
+        (0..self.len).map(|i| self.get(i).unwrap())
+    }
+}
+
+impl<'a, T> FromJava<'a> for JavaArray<'a, T> {
+    fn from_java(api: ModApi<'a>, object: JObject<'a>) -> Self {
+        let inner = JObjectArray::from(object);
+        let len = api.get_array_length(&inner);
+        JavaArray {
+            api,
+            inner,
+            len,
+            element: PhantomData,
+        }
+    }
 }
@@ -1,6 +1,7 @@
 pub use jni::sys::jint;
 pub use jni::{objects::JObject, JNIEnv, JNIVersion};
 
+pub use crate::object::JavaObject;
 pub use crate::object::RustObject;
 pub use crate::RustCraftMod;
 
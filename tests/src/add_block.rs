@@ -24,9 +24,7 @@ impl Block for MyBlock {
             //     false,
             // )
         }
-        // let test = rustcraft::net::minecraft::world::WorldAccess::new(world.api.clone(), unsafe {
-        //     JObject::from_raw(world.inner.clone())
-        // });
+        // let test: &rustcraft::net::minecraft::world::WorldAccess = world.as_ref();
         // test.get_server();
     }
     fn on_use<'a>(